@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use gameoflife::game::Game;
+
+const SIZES: [(u16, u16); 3] = [(50, 30), (200, 100), (500, 300)];
+const DENSITY: f64 = 0.3;
+
+/// Fills the game with a fixed xorshift sequence so every run benchmarks the
+/// same board
+fn random_game(width: u16, height: u16) -> Game {
+    let mut game = Game::new(width, height);
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    for y in 0..height {
+        for x in 0..width {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if (state >> 11) as f64 / (1u64 << 53) as f64 <= DENSITY {
+                game.revive_cell_at_pos(x, y).unwrap();
+            }
+        }
+    }
+    game
+}
+
+fn tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    for &(width, height) in SIZES.iter() {
+        let id = BenchmarkId::from_parameter(format!("{}x{}", width, height));
+        group.bench_function(id, |b| {
+            b.iter_batched(
+                || random_game(width, height),
+                |mut game| {
+                    game.tick();
+                    game
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tick);
+criterion_main!(benches);
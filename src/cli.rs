@@ -0,0 +1,637 @@
+use std::path::Path;
+
+use gameoflife::{Format, Game, Pattern, Rule};
+use log::LevelFilter;
+
+pub const DEFAULT_DENSITY: f64 = 0.3;
+/// File logged to when only `GAMEOFLIFE_LOG` is set
+pub const DEFAULT_LOG_FILE: &str = "gameoflife.log";
+
+/// Options given on the command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub fill: Fill,
+    /// Chance of each cell starting alive with `--fill random`
+    pub density: f64,
+    /// Cycle through the built-in patterns until a key is pressed
+    pub demo: bool,
+    /// Start playing instead of paused
+    pub play: bool,
+    /// Fixed width of the board, instead of following the terminal
+    pub width: Option<u16>,
+    /// Fixed height of the board, instead of following the terminal
+    pub height: Option<u16>,
+    /// Generations per second to start at
+    pub fps: f32,
+    /// File with a pattern to start with, in the middle of the board
+    pub pattern: Option<String>,
+    /// Format of the pattern piped in with `--headless`, instead of a
+    /// `--pattern` file
+    pub format: Option<Format>,
+    /// Recording to play again, instead of a pattern
+    pub replay: Option<String>,
+    /// Rule to start with, instead of B3/S23 or the one in the pattern file
+    pub rule: Option<Rule>,
+    /// Seed for `--fill random`, to get the same board again
+    pub seed: Option<u64>,
+    /// Chance of each cell flipping after every tick
+    pub mutation: f64,
+    /// Run without the terminal UI and print or write the board at the end
+    pub headless: bool,
+    /// Time ticking a random board, without the terminal UI
+    pub bench: bool,
+    /// Generations to run for in headless mode
+    pub generations: Option<u64>,
+    /// Generation to stop at: the TUI pauses there, headless mode ends there
+    pub max_generations: Option<u64>,
+    /// File to write the live cells to when quitting or, with `--headless`,
+    /// instead of printing them
+    pub out: Option<String>,
+    /// Whether the board and the bars are drawn in colour
+    pub color: ColorChoice,
+    /// File to write a log to, for debugging
+    pub log: Option<String>,
+    /// What goes in the log, instead of `GAMEOFLIFE_LOG` or debug
+    pub log_level: Option<LevelFilter>,
+}
+
+/// How the board starts out
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fill {
+    Empty,
+    Random,
+    Checkerboard,
+    /// A built-in pattern, stamped in the middle of the board
+    Preset(String),
+}
+
+/// When to draw in colour, from `--color` or `--no-color`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    /// Unless `NO_COLOR` is set, `TERM` is `dumb` or stdout isn't a terminal
+    Auto,
+    Always,
+    Never,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            fill: Fill::Empty,
+            density: DEFAULT_DENSITY,
+            demo: false,
+            play: false,
+            width: None,
+            height: None,
+            fps: crate::DEFAULT_FPS,
+            pattern: None,
+            format: None,
+            replay: None,
+            rule: None,
+            seed: None,
+            mutation: 0.0,
+            headless: false,
+            bench: false,
+            generations: None,
+            max_generations: None,
+            out: None,
+            color: ColorChoice::Auto,
+            log: None,
+            log_level: None,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the arguments after the program name. `Ok(None)` means the
+    /// usage was asked for with `--help`.
+    pub fn parse<I, S>(args: I) -> Result<Option<Self>, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+        let (mut fill_given, mut random_implied) = (false, false);
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--demo" => options.demo = true,
+                "--play" => options.play = true,
+                "--paused" => options.play = false,
+                "--headless" => options.headless = true,
+                "--bench" => options.bench = true,
+                "--color" => options.color = value()?.parse()?,
+                "--no-color" => options.color = ColorChoice::Never,
+                "--generations" => {
+                    options.generations = Some(parse_generations(&value()?)?);
+                }
+                "--max-generations" => {
+                    options.max_generations = Some(parse_generations(&value()?)?);
+                }
+                "--out" => {
+                    let file = value()?;
+                    // Checked now rather than when there's a board to lose
+                    if Format::from_path(Path::new(&file)).is_none() {
+                        return Err(format!(
+                            "invalid output file {:?}, expected .rle, .cells or .json",
+                            file
+                        ));
+                    }
+                    options.out = Some(file);
+                }
+                "--fill" => {
+                    options.fill = value()?.parse()?;
+                    fill_given = true;
+                }
+                "--density" => {
+                    let density = value()?;
+                    options.density = density
+                        .parse()
+                        .ok()
+                        .filter(|density| (0.0..=1.0).contains(density))
+                        .ok_or_else(|| format!("invalid density {:?}, expected 0 to 1", density))?;
+                    random_implied = true;
+                }
+                "--mutation" => {
+                    let rate = value()?;
+                    options.mutation = rate
+                        .parse()
+                        .ok()
+                        .filter(|rate| (0.0..=1.0).contains(rate))
+                        .ok_or_else(|| {
+                            format!("invalid mutation rate {:?}, expected 0 to 1", rate)
+                        })?;
+                }
+                "--pattern" => options.pattern = Some(value()?),
+                "--format" => {
+                    let format = value()?;
+                    options.format = Some(Format::from_name(&format).ok_or_else(|| {
+                        format!(
+                            "invalid format {:?}, expected rle, plaintext (or cells) or json",
+                            format
+                        )
+                    })?);
+                }
+                "--replay" => options.replay = Some(value()?),
+                "--log" => options.log = Some(value()?),
+                "--log-level" => options.log_level = Some(parse_log_level(&value()?)?),
+                "--rule" => options.rule = Some(value()?.parse()?),
+                "--seed" => {
+                    let seed = value()?;
+                    options.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("invalid seed {:?}, expected a number", seed))?,
+                    );
+                    random_implied = true;
+                }
+                "--size" => {
+                    let (width, height) = parse_size(&value()?)?;
+                    options.width = Some(width);
+                    options.height = Some(height);
+                }
+                "--width" => {
+                    let max = crate::MAX_BOARD_SIZE.0;
+                    options.width = Some(parse_dimension(&value()?, "width", max)?);
+                }
+                "--height" => {
+                    let max = crate::MAX_BOARD_SIZE.1;
+                    options.height = Some(parse_dimension(&value()?, "height", max)?);
+                }
+                "--fps" => {
+                    let fps = value()?;
+                    options.fps = fps
+                        .parse()
+                        .ok()
+                        .filter(|fps| (crate::MIN_FPS..=crate::MAX_FPS).contains(fps))
+                        .ok_or_else(|| {
+                            format!(
+                                "invalid fps {:?}, expected {} to {}",
+                                fps,
+                                crate::MIN_FPS,
+                                crate::MAX_FPS
+                            )
+                        })?;
+                }
+                _ => return Err(format!("unknown argument {:?}", flag)),
+            }
+        }
+
+        if options.format.is_some() && (!options.headless || options.pattern.is_some()) {
+            return Err("--format is for a pattern piped in with --headless".to_string());
+        }
+        // Asking for a density or seed is asking for a random board
+        if random_implied && !fill_given {
+            options.fill = Fill::Random;
+        }
+        Ok(Some(options))
+    }
+
+    /// Fixed board size from `--size`, `--width` or `--height`, with
+    /// `fallback` for the dimension that wasn't given. `None` if neither was.
+    pub fn size(&self, fallback: (u16, u16)) -> Option<(u16, u16)> {
+        if self.width.is_none() && self.height.is_none() {
+            return None;
+        }
+        Some((
+            self.width.unwrap_or(fallback.0),
+            self.height.unwrap_or(fallback.1),
+        ))
+    }
+
+    /// File and level to log at, given the `GAMEOFLIFE_LOG` level. Setting
+    /// just that logs to `DEFAULT_LOG_FILE`. `None` if neither it nor `--log`
+    /// is given, then nothing is logged.
+    pub fn log_target(
+        &self,
+        env_level: Option<&str>,
+    ) -> Result<Option<(String, LevelFilter)>, String> {
+        let file = match (&self.log, env_level) {
+            (Some(file), _) => file.clone(),
+            (None, Some(_)) => DEFAULT_LOG_FILE.to_string(),
+            (None, None) => return Ok(None),
+        };
+        let level = match (self.log_level, env_level) {
+            (Some(level), _) => level,
+            (None, Some(level)) => parse_log_level(level)?,
+            (None, None) => LevelFilter::Debug,
+        };
+        Ok(Some((file, level)))
+    }
+
+    /// Fills `game` as asked for by `--fill`, and sets the `--rule` and
+    /// `--mutation` rate. Returns the seed random boards were filled with,
+    /// the `--seed` or a new one.
+    pub fn apply(&self, game: &mut Game) -> Option<u64> {
+        if let Some(rule) = &self.rule {
+            game.set_rule(rule.clone());
+        }
+        game.set_mutation_rate(self.mutation);
+        if let Some(seed) = self.seed {
+            game.set_mutation_seed(seed);
+        }
+        match &self.fill {
+            Fill::Empty => {}
+            Fill::Random => {
+                return Some(match self.seed {
+                    Some(seed) => {
+                        game.randomize_with_seed(seed, self.density);
+                        seed
+                    }
+                    None => game.randomize(self.density),
+                });
+            }
+            Fill::Checkerboard => game.fill_checkerboard(),
+            Fill::Preset(name) => {
+                // Names are checked while parsing
+                game.stamp_centered(&Pattern::preset(name).unwrap());
+            }
+        }
+        None
+    }
+}
+
+/// Parses `WIDTHxHEIGHT`, each between 1 and the largest board size
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (max_width, max_height) = crate::MAX_BOARD_SIZE;
+    s.split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .filter(|&(width, height)| {
+            (1..=max_width).contains(&width) && (1..=max_height).contains(&height)
+        })
+        .ok_or_else(|| {
+            format!(
+                "invalid size {:?}, expected WIDTHxHEIGHT up to {}x{}",
+                s, max_width, max_height
+            )
+        })
+}
+
+/// Parses a number of generations
+fn parse_generations(s: &str) -> Result<u64, String> {
+    s.parse()
+        .map_err(|_| format!("invalid generations {:?}, expected a number", s))
+}
+
+/// Parses a log level, like `debug`
+fn parse_log_level(s: &str) -> Result<LevelFilter, String> {
+    s.parse().map_err(|_| {
+        format!(
+            "invalid log level {:?}, expected off, error, warn, info, debug or trace",
+            s
+        )
+    })
+}
+
+/// Parses a width or height between 1 and `max`
+fn parse_dimension(s: &str, name: &str, max: u16) -> Result<u16, String> {
+    s.parse()
+        .ok()
+        .filter(|dimension| (1..=max).contains(dimension))
+        .ok_or_else(|| format!("invalid {} {:?}, expected 1 to {}", name, s, max))
+}
+
+impl ColorChoice {
+    /// Whether to draw in colour, given whether `NO_COLOR` is set to
+    /// something, the `TERM` and whether stdout is a terminal
+    pub fn enabled(self, no_color: bool, term: Option<&str>, is_terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => !no_color && term != Some("dumb") && is_terminal,
+        }
+    }
+}
+
+impl std::str::FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(format!(
+                "invalid color {:?}, expected always, auto or never",
+                s
+            )),
+        }
+    }
+}
+
+impl std::str::FromStr for Fill {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "empty" => Ok(Self::Empty),
+            "random" => Ok(Self::Random),
+            "checkerboard" => Ok(Self::Checkerboard),
+            name if Pattern::preset(name).is_some() => Ok(Self::Preset(name.to_string())),
+            _ => Err(format!(
+                "unknown fill {:?}, expected empty, random, checkerboard or one of: {}",
+                s,
+                Pattern::preset_names().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+pub fn usage() -> String {
+    format!(
+        "Usage: gameoflife [OPTIONS]
+
+Options:
+  --fill <KIND>      How the board starts: empty (default), random,
+                     checkerboard, or a pattern ({})
+  --density <0..1>   Chance of a cell starting alive with --fill random,
+                     which it implies (default {})
+  --seed <N>         Seed for --fill random, which it implies, to start
+                     with the same board again for the same board size
+  --pattern <FILE>   Start with the pattern in FILE (.rle, .cells or
+                     .json) in the middle of the board, paused
+  --replay <FILE>    Play a run saved with the `record` command again,
+                     pausing where it ended
+  --rule <RULE>      Rule in B/S notation, like B36/S23 (default B3/S23,
+                     or the rule in the --pattern file)
+  --mutation <0..1>  Chance of each cell flipping after every generation,
+                     so the board never settles (default 0)
+  --play             Start playing, even on an empty board, which keeps
+                     ticking until something is drawn on it
+  --paused           Start paused (default)
+  --demo             Show the built-in patterns one after the other,
+                     until a key is pressed
+  --size <WxH>       Fixed board size, like 200x200, viewed through the
+                     terminal (default: the terminal size)
+  --width <N>        Fixed board width, the height follows the terminal
+  --height <N>       Fixed board height, the width follows the terminal
+  --fps <N>          Generations per second to start at (default {})
+  --headless         Run without the terminal UI, as fast as possible, and
+                     print the live cells at the end
+  --format <FORMAT>  Read the pattern from stdin with --headless, in rle,
+                     plaintext (or cells) or json
+  --bench            Time ticking a random board of --size (default
+                     256x256) at --density, without the terminal UI, and
+                     print the generations and cells updated per second
+  --generations <N>  Generations to run for with --headless (default 0),
+                     or --bench (default 100)
+  --max-generations <N>
+                     Pause once generation N is reached, or end there
+                     with --headless
+  --out <FILE>       Write the live cells to FILE (.rle, .cells or .json)
+                     when quitting, or at the end with --headless
+  --color <WHEN>     Draw in colour: always, never, or auto (default),
+                     which is unless NO_COLOR is set, TERM is dumb or
+                     the output isn't a terminal
+  --no-color         Draw with plain characters, like --color never
+  --log <FILE>       Write a log to FILE, for debugging (GAMEOFLIFE_LOG set
+                     to a level logs to {} too)
+  --log-level <LEVEL>
+                     What goes in the log: error, warn, info, debug
+                     (default) or trace, which has every event
+  -h, --help         Print this message",
+        Pattern::preset_names().collect::<Vec<_>>().join(", "),
+        DEFAULT_DENSITY,
+        crate::DEFAULT_FPS,
+        DEFAULT_LOG_FILE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        Options::parse(args.iter().copied())
+    }
+
+    #[test]
+    fn it_fills_a_checkerboard_from_the_command_line() {
+        let options = parse(&["--fill", "checkerboard"]).unwrap().unwrap();
+        let mut game = Game::new(7, 4);
+
+        options.apply(&mut game);
+
+        assert_eq!(options.fill, Fill::Checkerboard);
+        assert_eq!(game.population(), 14);
+    }
+
+    #[test]
+    fn it_parses_options() {
+        assert_eq!(parse(&[]), Ok(Some(Options::default())));
+        assert_eq!(parse(&["--help"]), Ok(None));
+        assert_eq!(
+            parse(&["--fill=random", "--density", "0.5"]),
+            Ok(Some(Options {
+                fill: Fill::Random,
+                density: 0.5,
+                ..Options::default()
+            }))
+        );
+        let sized = parse(&["--size", "200x150"]).unwrap().unwrap();
+        assert_eq!(sized.size((80, 24)), Some((200, 150)));
+        assert!(parse(&["--size", "0x10"]).is_err());
+        assert!(parse(&["--size", "10"]).is_err());
+        assert!(parse(&["--size", "5000x10"]).is_err());
+        assert!(parse(&["--width", "0"]).is_err());
+        assert!(parse(&["--fps", "0"]).is_err());
+        assert!(parse(&["--fps", "-3"]).is_err());
+        assert_eq!(
+            parse(&["--mutation", "0.001"]).unwrap().unwrap().mutation,
+            0.001
+        );
+        assert!(parse(&["--mutation", "2"]).is_err());
+        assert!(parse(&["--fps", "fast"]).is_err());
+        assert_eq!(
+            parse(&["--fill", "glider"]).unwrap().unwrap().fill,
+            Fill::Preset("glider".to_string())
+        );
+        assert!(parse(&["--demo"]).unwrap().unwrap().demo);
+        assert!(parse(&["--play"]).unwrap().unwrap().play);
+        assert!(!parse(&["--play", "--paused"]).unwrap().unwrap().play);
+        assert_eq!(
+            parse(&["--pattern", "gosper.rle"])
+                .unwrap()
+                .unwrap()
+                .pattern,
+            Some("gosper.rle".to_string())
+        );
+        assert!(parse(&["--pattern"]).is_err());
+        assert_eq!(
+            parse(&["--replay", "run.replay"]).unwrap().unwrap().replay,
+            Some("run.replay".to_string())
+        );
+        assert_eq!(
+            parse(&["--rule", "B36/S23"]).unwrap().unwrap().rule,
+            Some(Rule::new(&[3, 6], &[2, 3]))
+        );
+        assert!(parse(&["--rule", "B3"]).is_err());
+        let headless = parse(&["--headless", "--generations=50", "--out", "end.rle"])
+            .unwrap()
+            .unwrap();
+        assert!(headless.headless);
+        assert!(parse(&["--bench"]).unwrap().unwrap().bench);
+        assert_eq!(
+            parse(&["--headless", "--format", "rle"])
+                .unwrap()
+                .unwrap()
+                .format,
+            Some(Format::Rle)
+        );
+        assert!(parse(&["--headless", "--format", "txt"]).is_err());
+        assert!(parse(&["--format", "rle"]).is_err());
+        assert!(parse(&["--headless", "--format=rle", "--pattern", "a.rle"]).is_err());
+        assert_eq!(headless.generations, Some(50));
+        assert_eq!(headless.out, Some("end.rle".to_string()));
+        assert!(parse(&["--generations", "many"]).is_err());
+        assert!(parse(&["--out", "end.txt"]).is_err());
+        assert_eq!(
+            parse(&["--max-generations", "100"])
+                .unwrap()
+                .unwrap()
+                .max_generations,
+            Some(100)
+        );
+        assert!(parse(&["--fill", "nope"]).is_err());
+        assert!(parse(&["--fill"]).is_err());
+        assert!(parse(&["--density", "2"]).is_err());
+        assert!(parse(&["--speed"]).is_err());
+    }
+
+    #[test]
+    fn it_parses_the_size_and_speed() {
+        let options = parse(&["--width", "200", "--fps=30"]).unwrap().unwrap();
+
+        assert_eq!(options.fps, 30.0);
+        assert_eq!(options.size((80, 24)), Some((200, 24)));
+        assert_eq!(Options::default().size((80, 24)), None);
+    }
+
+    #[test]
+    fn it_fills_randomly_from_the_seed() {
+        let options = parse(&["--seed", "42", "--density", "0.25"])
+            .unwrap()
+            .unwrap();
+        let (mut first, mut second) = (Game::new(20, 10), Game::new(20, 10));
+
+        assert_eq!(options.fill, Fill::Random);
+        assert_eq!(options.apply(&mut first), Some(42));
+        options.apply(&mut second);
+        assert_eq!(
+            first.live_cells().collect::<Vec<_>>(),
+            second.live_cells().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            parse(&["--seed=1", "--fill", "checkerboard"])
+                .unwrap()
+                .unwrap()
+                .fill,
+            Fill::Checkerboard
+        );
+        assert!(parse(&["--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn it_decides_when_to_use_colour() {
+        let color = |args: &[&str]| parse(args).unwrap().unwrap().color;
+        assert_eq!(color(&[]), ColorChoice::Auto);
+        assert_eq!(color(&["--color", "always"]), ColorChoice::Always);
+        assert_eq!(color(&["--no-color"]), ColorChoice::Never);
+        assert!(parse(&["--color", "sometimes"]).is_err());
+
+        let auto = ColorChoice::Auto;
+        assert!(auto.enabled(false, Some("xterm-256color"), true));
+        assert!(auto.enabled(false, None, true));
+        assert!(!auto.enabled(true, Some("xterm-256color"), true));
+        assert!(!auto.enabled(false, Some("dumb"), true));
+        assert!(!auto.enabled(false, Some("xterm-256color"), false));
+        assert!(ColorChoice::Always.enabled(true, Some("dumb"), false));
+        assert!(!ColorChoice::Never.enabled(false, None, true));
+    }
+
+    #[test]
+    fn it_only_logs_when_asked_to() {
+        let target = |args: &[&str], env| parse(args).unwrap().unwrap().log_target(env);
+        assert_eq!(target(&[], None), Ok(None));
+        assert_eq!(
+            target(&["--log", "debug.log"], None),
+            Ok(Some(("debug.log".to_string(), LevelFilter::Debug)))
+        );
+        assert_eq!(
+            target(&[], Some("trace")),
+            Ok(Some((DEFAULT_LOG_FILE.to_string(), LevelFilter::Trace)))
+        );
+        assert_eq!(
+            target(
+                &["--log", "debug.log", "--log-level", "warn"],
+                Some("trace")
+            ),
+            Ok(Some(("debug.log".to_string(), LevelFilter::Warn)))
+        );
+        assert!(target(&[], Some("loud")).is_err());
+        assert!(parse(&["--log-level", "loud"]).is_err());
+    }
+
+    #[test]
+    fn it_stamps_presets_in_the_middle() {
+        let options = parse(&["--fill", "blinker"]).unwrap().unwrap();
+        let mut game = Game::new(9, 5);
+
+        options.apply(&mut game);
+
+        let live: Vec<_> = game.live_cells().collect();
+        assert_eq!(live, vec![(3, 2), (4, 2), (5, 2)]);
+    }
+}
@@ -0,0 +1,144 @@
+use gameoflife::Rule;
+
+use crate::ramp::{self, ColorRamp};
+
+/// Command typed in the command palette, opened with `:`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Kill every cell
+    Clear,
+    /// Fill the board randomly, with the given density or the default one
+    Random(Option<f64>),
+    /// Save to the given file, or the last one saved or loaded
+    Save(Option<String>),
+    Load(String),
+    Rule(Rule),
+    Fps(f32),
+    /// Colours for decaying cells
+    Ramp(ColorRamp),
+    /// Save the run since the board was last changed, to play it again
+    /// with `--replay`
+    Record(String),
+}
+
+/// Names of the commands, as typed
+pub const NAMES: [&str; 8] = [
+    "clear", "random", "save", "load", "rule", "fps", "ramp", "record",
+];
+
+impl std::str::FromStr for Command {
+    type Err = String;
+
+    /// Reads a command name followed by its argument, like `rule B36/S23`.
+    /// File names are the rest of the line, so they can have spaces.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, argument) = match s.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, Some(argument.trim())),
+            None => (s, None),
+        };
+        let needs = |what: &str| format!("{} needs {}", name, what);
+
+        match (name, argument) {
+            ("clear", None) => Ok(Self::Clear),
+            ("random", None) => Ok(Self::Random(None)),
+            ("random", Some(density)) => density
+                .parse()
+                .ok()
+                .filter(|density| (0.0..=1.0).contains(density))
+                .map(|density| Self::Random(Some(density)))
+                .ok_or_else(|| needs("a density from 0 to 1")),
+            ("save", file) => Ok(Self::Save(file.map(str::to_string))),
+            ("load", Some(file)) => Ok(Self::Load(file.to_string())),
+            ("load", None) => Err(needs("a file")),
+            ("record", Some(file)) => Ok(Self::Record(file.to_string())),
+            ("record", None) => Err(needs("a file")),
+            ("rule", Some(rule)) => Ok(Self::Rule(rule.parse()?)),
+            ("rule", None) => Err(needs("a rule, like B36/S23")),
+            ("fps", fps) => fps
+                .and_then(|fps| fps.parse().ok())
+                .filter(|fps| (crate::MIN_FPS..=crate::MAX_FPS).contains(fps))
+                .map(Self::Fps)
+                .ok_or_else(|| {
+                    needs(&format!(
+                        "a number from {} to {}",
+                        crate::MIN_FPS,
+                        crate::MAX_FPS
+                    ))
+                }),
+            ("ramp", name) => name
+                .and_then(ColorRamp::from_name)
+                .map(Self::Ramp)
+                .ok_or_else(|| {
+                    let names: Vec<_> = ramp::PRESETS.iter().map(ColorRamp::name).collect();
+                    needs(&format!("one of: {}", names.join(", ")))
+                }),
+            ("clear", Some(_)) => Err("clear doesn't take anything after it".to_string()),
+            _ => Err(format!(
+                "unknown command {:?}, expected one of: {}",
+                name,
+                NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Command, String> {
+        s.parse()
+    }
+
+    #[test]
+    fn it_parses_commands() {
+        assert_eq!(parse("clear"), Ok(Command::Clear));
+        assert_eq!(parse("random"), Ok(Command::Random(None)));
+        assert_eq!(parse("random 0.25"), Ok(Command::Random(Some(0.25))));
+        assert_eq!(parse("save"), Ok(Command::Save(None)));
+        assert_eq!(
+            parse("save my glider.rle"),
+            Ok(Command::Save(Some("my glider.rle".to_string())))
+        );
+        assert_eq!(
+            parse("  load  gosper.rle "),
+            Ok(Command::Load("gosper.rle".to_string()))
+        );
+        assert_eq!(
+            parse("rule B36/S23"),
+            Ok(Command::Rule(Rule::new(&[3, 6], &[2, 3])))
+        );
+        assert_eq!(parse("fps 10"), Ok(Command::Fps(10.0)));
+        assert_eq!(
+            parse("record run.replay"),
+            Ok(Command::Record("run.replay".to_string()))
+        );
+        assert_eq!(
+            parse("ramp grayscale"),
+            Ok(Command::Ramp(ColorRamp::GRAYSCALE))
+        );
+    }
+
+    #[test]
+    fn it_rejects_malformed_commands() {
+        assert_eq!(
+            parse("jump"),
+            Err(
+                "unknown command \"jump\", expected one of: clear, random, save, load, rule, fps, ramp, record"
+                    .to_string()
+            )
+        );
+        assert_eq!(parse("load"), Err("load needs a file".to_string()));
+        assert!(parse("").is_err());
+        assert!(parse("clear all").is_err());
+        assert!(parse("random 2").is_err());
+        assert!(parse("rule B9").is_err());
+        assert!(parse("fps").is_err());
+        assert!(parse("fps 0").is_err());
+        assert_eq!(
+            parse("ramp rainbow"),
+            Err("ramp needs one of: heat, grayscale".to_string())
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::io;
+
+use crate::format::ParseError;
+
+/// What can go wrong running a game, for callers to tell apart what's worth
+/// telling the user about from what they can't go on after.
+#[derive(Debug)]
+pub enum GameError {
+    /// Reading or writing the terminal or a file failed
+    Io(io::Error),
+    /// A position past the edges of the board
+    OutOfBounds {
+        /// Column asked for
+        x: u16,
+        /// Row asked for
+        y: u16,
+        /// Width of the board
+        width: u16,
+        /// Height of the board
+        height: u16,
+    },
+    /// A pattern that couldn't be read
+    Parse(ParseError),
+    /// A board too large to allocate
+    InvalidSize {
+        /// Width asked for
+        width: u16,
+        /// Height asked for
+        height: u16,
+        /// Most cells the board can have
+        max_cells: usize,
+    },
+    /// A chance outside `0..=1`
+    InvalidDensity(f64),
+    /// A pattern that doesn't fit on the board it's put on
+    PatternTooLarge {
+        /// Width of the pattern
+        pattern_width: u16,
+        /// Height of the pattern
+        pattern_height: u16,
+        /// Width of the board
+        width: u16,
+        /// Height of the board
+        height: u16,
+    },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Io(error) => write!(f, "{}", error),
+            GameError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "({}, {}) is outside the {}x{} board",
+                x, y, width, height
+            ),
+            GameError::Parse(error) => write!(f, "invalid pattern, {}", error),
+            GameError::InvalidSize {
+                width,
+                height,
+                max_cells,
+            } => write!(
+                f,
+                "a {}x{} board has more than {} cells",
+                width, height, max_cells
+            ),
+            GameError::InvalidDensity(density) => {
+                write!(f, "density {} isn't between 0 and 1", density)
+            }
+            GameError::PatternTooLarge {
+                pattern_width,
+                pattern_height,
+                width,
+                height,
+            } => write!(
+                f,
+                "a {}x{} pattern doesn't fit on a {}x{} board",
+                pattern_width, pattern_height, width, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Io(error) => Some(error),
+            GameError::Parse(error) => Some(error),
+            GameError::OutOfBounds { .. }
+            | GameError::InvalidSize { .. }
+            | GameError::InvalidDensity(_)
+            | GameError::PatternTooLarge { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for GameError {
+    fn from(error: io::Error) -> Self {
+        GameError::Io(error)
+    }
+}
+
+impl From<ParseError> for GameError {
+    fn from(error: ParseError) -> Self {
+        GameError::Parse(error)
+    }
+}
@@ -0,0 +1,595 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::pattern::Pattern;
+
+/// Longest line written in RLE files, as most readers expect
+const RLE_LINE_WIDTH: usize = 70;
+
+/// File formats patterns can be saved in and loaded from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Run length encoded, `.rle`
+    Rle,
+    /// Rows of `O` and `.`, `.cells`
+    Plaintext,
+    /// `{"width": .., "height": .., "cells": [[x, y], ..]}`, `.json`
+    Json,
+}
+
+impl Format {
+    /// The format going with the extension of `path`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rle" => Some(Format::Rle),
+            "cells" => Some(Format::Plaintext),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// The format with this name, or the extension going with it
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rle" => Some(Format::Rle),
+            "plaintext" | "cells" => Some(Format::Plaintext),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Reads a pattern written in this format
+    pub fn parse(self, text: &str) -> Result<Pattern, ParseError> {
+        match self {
+            Format::Rle => parse_rle(text),
+            Format::Plaintext => parse_plaintext(text),
+            Format::Json => parse_json(text),
+        }
+    }
+
+    /// Writes `pattern` in this format
+    pub fn write(self, pattern: &Pattern) -> String {
+        match self {
+            Format::Rle => to_rle(pattern),
+            Format::Plaintext => to_plaintext(pattern),
+            Format::Json => to_json(pattern),
+        }
+    }
+}
+
+/// Why a pattern couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based line the problem is on
+    pub line: usize,
+    /// What's wrong with it
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn error(line: usize, message: impl Into<String>) -> ParseError {
+    ParseError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// Reads a pattern in either format, trying RLE first.
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    parse_rle(text).or_else(|rle_error| {
+        // Only texts with an RLE header are worth reporting RLE errors for
+        let has_header = text.lines().any(is_rle_header);
+        parse_plaintext(text).map_err(|plaintext_error| {
+            if has_header {
+                rle_error
+            } else {
+                plaintext_error
+            }
+        })
+    })
+}
+
+/// Reads the run length encoded format, like:
+///
+/// ```text
+/// #N Glider
+/// x = 3, y = 3, rule = B3/S23
+/// bob$2bo$3o!
+/// ```
+///
+/// The size and rule in the header are ignored, the pattern is sized to fit
+/// its cells.
+pub fn parse_rle(text: &str) -> Result<Pattern, ParseError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (header_line, header) = lines
+        .next()
+        .ok_or_else(|| error(1, "expected an `x = ..., y = ...` header"))?;
+    if !is_rle_header(header) {
+        return Err(error(
+            header_line,
+            format!("expected an `x = ..., y = ...` header, got {:?}", header),
+        ));
+    }
+
+    let mut cells = vec![];
+    let (mut x, mut y) = (0u16, 0u16);
+    let mut count: Option<u16> = None;
+    let mut last_line = header_line;
+    for (number, line) in lines {
+        last_line = number;
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c as u16 - '0' as u16;
+                    count = count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit));
+                    if count.is_none() {
+                        return Err(error(number, "run too long"));
+                    }
+                }
+                'b' | '.' => x = advance(x, count.take(), number)?,
+                'o' | 'A'..='X' => {
+                    let run = count.take().unwrap_or(1);
+                    for dx in 0..run {
+                        let cell_x = x
+                            .checked_add(dx)
+                            .ok_or_else(|| error(number, "pattern too wide"))?;
+                        cells.push((cell_x, y));
+                    }
+                    x = advance(x, Some(run), number)?;
+                }
+                '$' => {
+                    y = advance(y, count.take(), number)?;
+                    x = 0;
+                }
+                '!' => return Ok(Pattern::new(cells)),
+                c if c.is_whitespace() => (),
+                c => return Err(error(number, format!("unexpected {:?}", c))),
+            }
+        }
+    }
+    Err(error(last_line, "missing `!` at the end"))
+}
+
+/// The `rule = ...` part of an RLE header, like `B36/S23`, when there is one
+pub fn rle_rule(text: &str) -> Option<&str> {
+    let header = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| is_rle_header(line))?;
+    header
+        .split(',')
+        .filter_map(|part| part.split_once('='))
+        .find(|(name, _)| name.trim() == "rule")
+        .map(|(_, rule)| rule.trim())
+}
+
+fn is_rle_header(line: &str) -> bool {
+    line.split(',')
+        .next()
+        .and_then(|x| x.split_once('='))
+        .is_some_and(|(name, _)| name.trim() == "x")
+}
+
+fn advance(pos: u16, run: Option<u16>, line: usize) -> Result<u16, ParseError> {
+    pos.checked_add(run.unwrap_or(1))
+        .ok_or_else(|| error(line, "pattern too large"))
+}
+
+/// Reads the plaintext format, where `O` is a live cell, `.` a dead one, and
+/// lines starting with `!` are comments:
+///
+/// ```text
+/// !Name: Glider
+/// .O.
+/// ..O
+/// OOO
+/// ```
+pub fn parse_plaintext(text: &str) -> Result<Pattern, ParseError> {
+    let mut cells = vec![];
+    let mut y = 0u16;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('!') {
+            continue;
+        }
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                'O' | '*' => cells.push((x as u16, y)),
+                '.' => (),
+                c => return Err(error(index + 1, format!("unexpected {:?}", c))),
+            }
+        }
+        y += 1;
+    }
+    Ok(Pattern::new(cells))
+}
+
+/// Writes the pattern as RLE, with runs of dead cells at the end of rows left
+/// out and lines wrapped at 70 characters.
+pub fn to_rle(pattern: &Pattern) -> String {
+    let (width, height) = pattern.size();
+    // Runs of `b`, `o` and `$`, merged as they're pushed
+    let mut runs: Vec<(u16, char)> = vec![];
+    for y in 0..height {
+        if y > 0 {
+            push_run(&mut runs, 1, '$');
+        }
+        let mut dead = 0;
+        for x in 0..width {
+            if pattern.is_alive(x, y) {
+                if dead > 0 {
+                    push_run(&mut runs, dead, 'b');
+                    dead = 0;
+                }
+                push_run(&mut runs, 1, 'o');
+            } else {
+                dead += 1;
+            }
+        }
+    }
+
+    let mut out = format!("x = {}, y = {}\n", width, height);
+    let mut line = String::new();
+    let items = runs.iter().map(|&(count, tag)| match count {
+        1 => tag.to_string(),
+        count => format!("{}{}", count, tag),
+    });
+    for item in items.chain(std::iter::once("!".to_string())) {
+        if line.len() + item.len() > RLE_LINE_WIDTH {
+            out.push_str(&line);
+            out.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+/// Adds a run to `runs`, merging it with the last one if it's the same tag
+fn push_run(runs: &mut Vec<(u16, char)>, count: u16, tag: char) {
+    match runs.last_mut() {
+        Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+        _ => runs.push((count, tag)),
+    }
+}
+
+/// Writes the pattern as rows of `O` and `.`, as wide as the pattern.
+pub fn to_plaintext(pattern: &Pattern) -> String {
+    let (width, height) = pattern.size();
+    let mut out = String::new();
+    for y in 0..height {
+        out.extend((0..width).map(|x| if pattern.is_alive(x, y) { 'O' } else { '.' }));
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes the pattern as a JSON object with its size and its live cells.
+pub fn to_json(pattern: &Pattern) -> String {
+    let (width, height) = pattern.size();
+    let cells: Vec<String> = pattern
+        .cells()
+        .iter()
+        .map(|(x, y)| format!("[{}, {}]", x, y))
+        .collect();
+    format!(
+        "{{\"width\": {}, \"height\": {}, \"cells\": [{}]}}\n",
+        width,
+        height,
+        cells.join(", ")
+    )
+}
+
+/// Reads a pattern written by `to_json`. Other keys are ignored, and the
+/// size is grown if needed to fit the cells.
+pub fn parse_json(text: &str) -> Result<Pattern, ParseError> {
+    let mut parser = JsonParser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("unexpected text after the pattern"));
+    }
+
+    let fields = match value {
+        Json::Object(fields) => fields,
+        _ => return Err(error(1, "expected an object")),
+    };
+    let field = |name: &str| fields.iter().find(|(key, _)| key == name).map(|(_, v)| v);
+    let size = |name: &str| match field(name) {
+        Some(Json::Number(n)) => to_u16(*n).ok_or_else(|| error(1, format!("invalid {}", name))),
+        None => Ok(0),
+        Some(_) => Err(error(1, format!("{} should be a number", name))),
+    };
+    let (width, height) = (size("width")?, size("height")?);
+    let cells = match field("cells") {
+        Some(Json::Array(cells)) => cells,
+        _ => return Err(error(1, "expected a `cells` array")),
+    };
+    let cells = cells
+        .iter()
+        .map(|cell| match cell {
+            Json::Array(pos) => match pos.as_slice() {
+                [Json::Number(x), Json::Number(y)] => to_u16(*x).zip(to_u16(*y)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| error(1, "cells should be [x, y] pairs"))?;
+    Ok(Pattern::with_size(width, height, cells))
+}
+
+fn to_u16(n: f64) -> Option<u16> {
+    if n.fract() == 0.0 && (0.0..=f64::from(u16::MAX)).contains(&n) {
+        Some(n as u16)
+    } else {
+        None
+    }
+}
+
+/// The JSON values `parse_json` needs, strings only as object keys
+enum Json {
+    Number(f64),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+    Other,
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    /// Byte offset of the next character
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        error(line, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    /// Skips whitespace, then `expected` if it's next
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", expected)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let mut fields = vec![];
+                if self.eat('}') {
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    if self.eat('}') {
+                        return Ok(Json::Object(fields));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut items = vec![];
+                if self.eat(']') {
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    if self.eat(']') {
+                        return Ok(Json::Array(items));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            Some('"') => self.string().map(|_| Json::Other),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let rest = &self.text[self.pos..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                    .unwrap_or(rest.len());
+                let number = rest[..len]
+                    .parse()
+                    .map_err(|_| self.error("invalid number"))?;
+                self.pos += len;
+                Ok(Json::Number(number))
+            }
+            _ => {
+                for word in ["true", "false", "null"] {
+                    if self.text[self.pos..].starts_with(word) {
+                        self.pos += word.len();
+                        return Ok(Json::Other);
+                    }
+                }
+                Err(self.error("expected a value"))
+            }
+        }
+    }
+
+    /// A string without escapes, which the keys we read don't need
+    fn string(&mut self) -> Result<String, ParseError> {
+        if self.peek() != Some('"') {
+            return Err(self.error("expected a string"));
+        }
+        let rest = &self.text[self.pos + 1..];
+        let len = rest
+            .find('"')
+            .ok_or_else(|| self.error("unterminated string"))?;
+        let string = rest[..len].to_string();
+        self.pos += len + 2;
+        Ok(string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_rle() {
+        let glider = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+
+        assert_eq!(parse_rle(glider), Ok(Pattern::preset("glider").unwrap()));
+        assert_eq!(rle_rule(glider), Some("B3/S23"));
+        assert_eq!(rle_rule("x = 2, y = 1\n2o!"), None);
+        assert_eq!(rle_rule(".O\nrule = B3/S23"), None);
+        // Runs of line ends skip empty rows, and the body can span lines
+        assert_eq!(
+            parse_rle("x = 2, y = 3\n2o\n2$\nbo!"),
+            Ok(Pattern::new(vec![(0, 0), (1, 0), (1, 2)]))
+        );
+    }
+
+    #[test]
+    fn it_reports_rle_errors_with_the_line() {
+        assert_eq!(
+            parse_rle("x = 3, y = 3\nbob$2bo$3o"),
+            Err(error(2, "missing `!` at the end"))
+        );
+        assert_eq!(
+            parse_rle("#C comment\nx = 3, y = 1\nbzb!"),
+            Err(error(3, "unexpected 'z'"))
+        );
+        assert!(parse_rle(".O.\n..O\nOOO").is_err());
+    }
+
+    #[test]
+    fn it_parses_plaintext() {
+        let glider = "!Name: Glider\n.O.\n..O\nOOO\n";
+
+        assert_eq!(
+            parse_plaintext(glider),
+            Ok(Pattern::preset("glider").unwrap())
+        );
+        assert_eq!(parse_plaintext("..\nOx"), Err(error(2, "unexpected 'x'")));
+    }
+
+    #[test]
+    fn it_writes_rle() {
+        let glider = Pattern::preset("glider").unwrap();
+        assert_eq!(to_rle(&glider), "x = 3, y = 3\nbo$2bo$3o!\n");
+
+        // Empty rows merge into one run of line ends
+        let spaced = Pattern::new(vec![(0, 0), (1, 0), (3, 3)]);
+        assert_eq!(to_rle(&spaced), "x = 4, y = 4\n2o3$3bo!\n");
+
+        let gun = Pattern::preset("gosper-gun").unwrap();
+        let rle = to_rle(&gun);
+        assert!(rle.lines().all(|line| line.len() <= RLE_LINE_WIDTH));
+        assert_eq!(parse_rle(&rle), Ok(gun));
+    }
+
+    #[test]
+    fn it_round_trips_every_format() {
+        let margins = Pattern::with_size(5, 4, vec![(1, 1), (2, 1), (3, 2)]);
+        for format in [Format::Rle, Format::Plaintext, Format::Json] {
+            let glider = Pattern::preset("glider").unwrap();
+            assert_eq!(format.parse(&format.write(&glider)), Ok(glider));
+        }
+        // Dead margins are only kept where the format says the size
+        assert_eq!(parse_json(&to_json(&margins)), Ok(margins.clone()));
+        assert_eq!(
+            parse_plaintext(&to_plaintext(&margins)).unwrap().size(),
+            (4, 3)
+        );
+    }
+
+    #[test]
+    fn it_reads_json() {
+        assert_eq!(
+            parse_json(
+                "{\"name\": \"glider\", \"cells\": [[1, 0], [2, 1], [0, 2], [1, 2], [2, 2]]}"
+            ),
+            Ok(Pattern::preset("glider").unwrap())
+        );
+        assert_eq!(
+            parse_json("{\"cells\": [[1, 0]],\n \"width\": true}").unwrap_err(),
+            error(1, "width should be a number")
+        );
+        assert_eq!(
+            parse_json("{\"cells\":\n [[1, 0], [2]]}").unwrap_err(),
+            error(1, "cells should be [x, y] pairs")
+        );
+        assert_eq!(parse_json("{\"cells\":\n [1,, 2]}").unwrap_err().line, 2);
+        assert!(parse_json("[]").is_err());
+    }
+
+    #[test]
+    fn it_picks_the_format_from_the_extension() {
+        assert_eq!(
+            Format::from_path(Path::new("a/glider.rle")),
+            Some(Format::Rle)
+        );
+        assert_eq!(
+            Format::from_path(Path::new("glider.cells")),
+            Some(Format::Plaintext)
+        );
+        assert_eq!(
+            Format::from_path(Path::new("glider.json")),
+            Some(Format::Json)
+        );
+        assert_eq!(Format::from_path(Path::new("glider.txt")), None);
+        assert_eq!(Format::from_path(Path::new("glider")), None);
+        assert_eq!(Format::from_name("plaintext"), Some(Format::Plaintext));
+        assert_eq!(Format::from_name("cells"), Some(Format::Plaintext));
+        assert_eq!(Format::from_name("txt"), None);
+    }
+
+    #[test]
+    fn it_tries_rle_then_plaintext() {
+        let glider = Pattern::preset("glider").unwrap();
+
+        assert_eq!(parse("x = 3, y = 3\nbob$2bo$3o!"), Ok(glider.clone()));
+        assert_eq!(parse(".O.\n..O\nOOO"), Ok(glider));
+        assert_eq!(
+            parse("x = 3, y = 3\nbob$2bo$3o").unwrap_err().message,
+            "missing `!` at the end"
+        );
+        assert_eq!(parse("hello").unwrap_err(), error(1, "unexpected 'h'"));
+    }
+}
@@ -1,234 +1,2217 @@
-#[derive(Debug, PartialEq)]
-enum CellKind {
+use std::collections::HashMap;
+
+use crate::error::GameError;
+use crate::pattern::Pattern;
+use crate::rng::{self, Rng};
+use crate::rule::{Rule, TransitionRule};
+
+/// State of a cell. More may be added, for other kinds of rules.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[non_exhaustive]
+pub enum CellKind {
+    /// Alive, counted as a neighbour
     Alive,
+    /// Decaying cell of a Generations rule, counting down from `states - 1`
+    /// and dead after 2
+    Dying(u8),
+    /// Dead, and can be born
     Dead,
 }
 
-#[derive(Debug, PartialEq)]
+/// Colour painted on a cell, passed on to the cells it gives birth to
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Color {
+    /// Red
+    pub r: u8,
+    /// Green
+    pub g: u8,
+    /// Blue
+    pub b: u8,
+}
+
+/// A square of the board, alive, dead or decaying, that knows where it is
+#[derive(Debug, PartialEq, Clone)]
 pub struct Cell {
     x: u16,
     y: u16,
     kind: CellKind,
+    /// Ticks the cell has been alive for in a row
+    age: u32,
+    color: Option<Color>,
 }
 
 impl Cell {
+    /// Whether it's alive, decaying cells aren't
     pub fn is_alive(&self) -> bool {
         match self.kind {
             CellKind::Alive => true,
-            CellKind::Dead => false,
+            CellKind::Dying(_) | CellKind::Dead => false,
+        }
+    }
+
+    /// State of a decaying cell, from `states - 1` down to 2, the last one
+    /// before it's dead, or `None` if the cell isn't decaying.
+    pub fn dying(&self) -> Option<u8> {
+        match self.kind {
+            CellKind::Dying(left) => Some(left),
+            CellKind::Alive | CellKind::Dead => None,
         }
     }
 
-    fn new(x: u16, y: u16) -> Self {
+    /// Alive, decaying or dead
+    pub fn kind(&self) -> CellKind {
+        self.kind
+    }
+
+    /// Where the cell is on the board, as `(x, y)`
+    pub fn position(&self) -> Position {
+        (self.x, self.y)
+    }
+
+    /// Column of the cell
+    pub fn x(&self) -> u16 {
+        self.x
+    }
+
+    /// Row of the cell
+    pub fn y(&self) -> u16 {
+        self.y
+    }
+
+    /// Number of ticks a live cell has survived since it was born, `0` for
+    /// newborn and dead cells.
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+
+    /// Colour the cell was painted or born with, `None` for uncoloured and
+    /// dead cells
+    pub fn color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// A newborn uncoloured cell at `(x, y)`
+    pub fn alive(x: u16, y: u16) -> Self {
         Self {
-            x,
-            y,
-            kind: CellKind::Dead,
+            kind: CellKind::Alive,
+            ..Self::dead(x, y)
         }
     }
 
-    fn new_alive(x: u16, y: u16) -> Self {
+    /// A dead cell at `(x, y)`
+    pub fn dead(x: u16, y: u16) -> Self {
         Self {
             x,
             y,
-            kind: CellKind::Alive,
+            kind: CellKind::Dead,
+            age: 0,
+            color: None,
         }
     }
 
+    /// Revives the cell. Cells that were alive already keep their age.
     fn live(&mut self) {
-        self.kind = CellKind::Alive;
+        if !self.is_alive() {
+            self.kind = CellKind::Alive;
+            self.age = 0;
+        }
+    }
+
+    /// Whether the cell is in the outermost rows or columns of a board this
+    /// size
+    fn on_border(&self, width: u16, height: u16) -> bool {
+        self.x == 0 || self.y == 0 || self.x + 1 == width || self.y + 1 == height
     }
 
     fn die(&mut self) {
         self.kind = CellKind::Dead;
+        self.age = 0;
+        self.color = None;
+    }
+
+    /// The cell as it is next tick, aging it if it stays alive. Cells that
+    /// are born get `born_color`, the others keep theirs until they're dead.
+    fn next(&self, kind: CellKind, born_color: impl FnOnce() -> Option<Color>) -> Self {
+        let (age, color) = match (self.kind, kind) {
+            (CellKind::Alive, CellKind::Alive) => (self.age.saturating_add(1), self.color),
+            (_, CellKind::Alive) => (0, born_color()),
+            (_, CellKind::Dying(_)) => (0, self.color),
+            (_, CellKind::Dead) => (0, None),
+        };
+        Self {
+            kind,
+            age,
+            color,
+            ..*self
+        }
+    }
+}
+
+/// Column and row of a cell
+pub type Position = (u16, u16);
+
+/// What happens to neighbours that fall outside the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Topology {
+    /// Cells outside the board are always dead
+    Bounded,
+    /// Opposite edges are connected, the board wraps around
+    Toroidal,
+    /// The edge rows and columns act as mirrors, so cells outside the board
+    /// are reflections of the ones inside
+    Mirror,
+}
+
+impl Topology {
+    /// What happens at the edges, in a word
+    pub fn name(self) -> &'static str {
+        match self {
+            Topology::Bounded => "bounded",
+            Topology::Toroidal => "wrapping",
+            Topology::Mirror => "mirrored",
+        }
+    }
+
+    /// The topology after this one, to cycle through them
+    pub fn next(self) -> Self {
+        match self {
+            Topology::Bounded => Topology::Toroidal,
+            Topology::Toroidal => Topology::Mirror,
+            Topology::Mirror => Topology::Bounded,
+        }
+    }
+
+    /// Maps a possibly out of bounds coordinate back into `0..len`
+    fn wrap(self, i: i32, len: u16) -> Option<usize> {
+        let len = i32::from(len);
+        let i = match self {
+            _ if (0..len).contains(&i) => i,
+            Topology::Bounded => return None,
+            Topology::Toroidal => i.rem_euclid(len),
+            Topology::Mirror if i < 0 => -i,
+            Topology::Mirror => 2 * (len - 1) - i,
+        };
+        if (0..len).contains(&i) {
+            Some(i as usize)
+        } else {
+            None
+        }
+    }
+}
+
+/// Which cells around a cell count as its neighbours
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Neighbourhood {
+    /// The 8 cells around it, diagonals included
+    Moore,
+    /// Only the 4 cells above, below, left and right of it
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    /// Positions of the neighbours relative to the cell
+    fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Neighbourhood::Moore => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+            Neighbourhood::VonNeumann => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+        }
     }
 }
 
+/// How many rows/columns are added at once when the board auto-grows.
+const GROW_CHUNK: u16 = 16;
+
+/// Most cells a board can have unless `set_max_cells` says otherwise, a few
+/// hundred megabytes' worth
+pub const DEFAULT_MAX_CELLS: usize = 10_000_000;
+
+/// A board of cells and the rule, topology and neighbourhood it ticks by
 pub struct Game {
     cells: Vec<Vec<Cell>>,
+    /// The previous generation's cells, kept so ticking can write the next
+    /// one over them instead of allocating a new board every time
+    next: Vec<Vec<Cell>>,
+    auto_grow: bool,
+    border_dead: bool,
+    preserve_on_resize: bool,
+    max_size: Option<(u16, u16)>,
+    max_cells: usize,
+    origin: (u16, u16),
+    rule: Rule,
+    /// Used instead of `rule` when set
+    custom_rule: Option<Box<dyn TransitionRule>>,
+    topology: Topology,
+    neighbourhood: Neighbourhood,
+    revision: u64,
+    generation: u64,
+    /// Chance of each cell flipping after every tick
+    mutation_rate: f64,
+    mutation_rng: Rng,
 }
 
 impl Game {
+    /// Creates a board of dead cells. A zero width or height makes an empty
+    /// 0x0 board, which ticks and resizes like any other.
+    ///
+    /// # Panics
+    ///
+    /// If it would have more than `DEFAULT_MAX_CELLS` cells, rather than
+    /// trying to allocate that much.
     pub fn new(width: u16, height: u16) -> Self {
+        match Self::try_new(width, height) {
+            Ok(game) => game,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Like `new`, but a board with more than `DEFAULT_MAX_CELLS` cells is
+    /// an error.
+    pub fn try_new(width: u16, height: u16) -> Result<Self, GameError> {
+        GameBuilder::new(width, height).build()
+    }
+
+    /// Starts a `GameBuilder` for a board of this size, for setting more
+    /// than the size up front.
+    pub fn builder(width: u16, height: u16) -> GameBuilder {
+        GameBuilder::new(width, height)
+    }
+
+    /// Every cell with its position, collected into a `Vec`. Prefer
+    /// `iter_cells`, which doesn't allocate.
+    pub fn cells(&self) -> Vec<(&Cell, (u16, u16))> {
+        self.iter_cells()
+            .map(|(cell, x, y)| (cell, (x, y)))
+            .collect()
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter_cells(&self) -> impl Iterator<Item = (&Cell, u16, u16)> {
+        self.cells.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, cell)| (cell, x as u16, y as u16))
+        })
+    }
+
+    /// Errors with `GameError::OutOfBounds` off the board, like the other
+    /// methods changing a single cell.
+    pub fn revive_cell_at_pos(&mut self, x: u16, y: u16) -> Result<(), GameError> {
+        self.cell_at_pos_mut(x, y)?.live();
+        Ok(())
+    }
+
+    /// Kills the cell at `(x, y)`.
+    pub fn kill_cell_at_pos(&mut self, x: u16, y: u16) -> Result<(), GameError> {
+        self.cell_at_pos_mut(x, y)?.die();
+        Ok(())
+    }
+
+    /// Paints the cell, which only shows while it's alive. `None` removes
+    /// its colour.
+    pub fn set_cell_color(
+        &mut self,
+        x: u16,
+        y: u16,
+        color: Option<Color>,
+    ) -> Result<(), GameError> {
+        self.cell_at_pos_mut(x, y)?.color = color;
+        Ok(())
+    }
+
+    /// Kills the cell if it's alive, otherwise revives it.
+    pub fn toggle_cell_at_pos(&mut self, x: u16, y: u16) -> Result<(), GameError> {
+        let cell = self.cell_at_pos_mut(x, y)?;
+        if cell.is_alive() {
+            cell.die();
+        } else {
+            cell.live();
+        }
+        Ok(())
+    }
+
+    /// Makes the board grow in every direction when live cells get close to
+    /// its edges, so patterns behave as if the board was infinite. Only has an
+    /// effect with the `Bounded` topology.
+    pub fn set_auto_grow(&mut self, auto_grow: bool) {
+        self.auto_grow = auto_grow;
+    }
+
+    /// Whether the board grows, see `set_auto_grow`
+    pub fn auto_grow(&self) -> bool {
+        self.auto_grow
+    }
+
+    /// Keeps the outermost rows and columns dead after every tick, walling the
+    /// board in with a frame nothing can cross or live on.
+    pub fn set_border_dead(&mut self, border_dead: bool) {
+        self.border_dead = border_dead;
+    }
+
+    /// Whether the edges are kept dead, see `set_border_dead`
+    pub fn border_dead(&self) -> bool {
+        self.border_dead
+    }
+
+    /// Makes `resize` keep the cells that don't fit in the new size, leaving
+    /// the board larger than asked for instead of dropping them.
+    ///
+    /// This also covers the rows and columns auto-growing added: with it off,
+    /// resizing drops everything past the new size from the top-left corner
+    /// of the board, including live cells that have grown the board.
+    pub fn set_preserve_on_resize(&mut self, preserve_on_resize: bool) {
+        self.preserve_on_resize = preserve_on_resize;
+    }
+
+    /// Whether resizing keeps every cell, see `set_preserve_on_resize`
+    pub fn preserve_on_resize(&self) -> bool {
+        self.preserve_on_resize
+    }
+
+    /// Limits how large auto-growing can make the board. `None` means no limit
+    /// other than `u16::MAX` in each dimension.
+    pub fn set_max_size(&mut self, max_size: Option<(u16, u16)>) {
+        self.max_size = max_size;
+    }
+
+    /// Limits how many cells resizing and auto-growing can make the board
+    /// have, `DEFAULT_MAX_CELLS` to start with. Growing past it is refused.
+    pub fn set_max_cells(&mut self, max_cells: usize) {
+        self.max_cells = max_cells;
+    }
+
+    /// Most cells the board can have, see `set_max_cells`
+    pub fn max_cells(&self) -> usize {
+        self.max_cells
+    }
+
+    fn fits(&self, width: u16, height: u16) -> bool {
+        usize::from(width) * usize::from(height) <= self.max_cells
+    }
+
+    /// Position of the cell that was originally at (0, 0). It moves right and
+    /// down every time the board grows to the left or to the top.
+    pub fn origin(&self) -> (u16, u16) {
+        self.origin
+    }
+
+    /// Counter bumped whenever the cells change, to cheaply tell whether
+    /// something computed from them is stale.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Positions of the live cells, row by row.
+    pub fn live_cells(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_alive())
+            .map(|cell| (cell.x, cell.y))
+    }
+
+    /// Number of ticks since the game started.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Number of live cells on the board.
+    pub fn population(&self) -> usize {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|cell| cell.is_alive())
+            .count()
+    }
+
+    /// Revives the cells of `pattern` with its top-left corner at `(x, y)`.
+    /// Cells falling outside the board are left out, in which case it returns
+    /// `false`.
+    pub fn stamp(&mut self, pattern: &Pattern, x: u16, y: u16) -> bool {
+        let mut fits = true;
+        for &(dx, dy) in pattern.cells() {
+            let revived = x
+                .checked_add(dx)
+                .zip(y.checked_add(dy))
+                .is_some_and(|(x, y)| self.revive_cell_at_pos(x, y).is_ok());
+            fits &= revived;
+        }
+        fits
+    }
+
+    /// Stamps `pattern` in the middle of the board.
+    pub fn stamp_centered(&mut self, pattern: &Pattern) -> bool {
+        let (width, height) = self.size();
+        let (pattern_width, pattern_height) = pattern.size();
+        self.stamp(
+            pattern,
+            width.saturating_sub(pattern_width) / 2,
+            height.saturating_sub(pattern_height) / 2,
+        )
+    }
+
+    /// Like `stamp`, but the dead cells of `pattern` are killed too, replacing
+    /// everything it covers.
+    pub fn stamp_overwriting(&mut self, pattern: &Pattern, x: u16, y: u16) -> bool {
+        let (width, height) = pattern.size();
+        for dy in 0..height {
+            for dx in 0..width {
+                if !pattern.is_alive(dx, dy) {
+                    if let Some((x, y)) = x.checked_add(dx).zip(y.checked_add(dy)) {
+                        // Left out off the board, like by `stamp`
+                        let _ = self.kill_cell_at_pos(x, y);
+                    }
+                }
+            }
+        }
+        self.stamp(pattern, x, y)
+    }
+
+    /// Copies the cells inside the inclusive `(min_x, min_y, max_x, max_y)`
+    /// bounds, the same form `live_bounds` returns. The pattern keeps the size
+    /// of the region, clipped to the board, even where it's dead.
+    pub fn copy_region(&self, bounds: (u16, u16, u16, u16)) -> Pattern {
+        let (min_x, min_y, max_x, max_y) = bounds;
+        let (width, height) = self.size();
+        let max_x = max_x.min(width.saturating_sub(1));
+        let max_y = max_y.min(height.saturating_sub(1));
+        if width == 0 || height == 0 || min_x > max_x || min_y > max_y {
+            return Pattern::new(vec![]);
+        }
+
         let mut cells = vec![];
-        for y in 0..height {
-            let mut row = vec![];
-            for x in 0..width {
-                let cell = Cell::new(x, y);
-                row.push(cell);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if self.find_cell_at_pos(x, y).is_some_and(Cell::is_alive) {
+                    cells.push((x - min_x, y - min_y));
+                }
+            }
+        }
+        Pattern::with_size(max_x - min_x + 1, max_y - min_y + 1, cells)
+    }
+
+    /// Kills every cell inside the inclusive `(min_x, min_y, max_x, max_y)`
+    /// bounds.
+    pub fn clear_region(&mut self, bounds: (u16, u16, u16, u16)) {
+        let (min_x, min_y, max_x, max_y) = bounds;
+        for cell in self.cells.iter_mut().flatten() {
+            if (min_x..=max_x).contains(&cell.x) && (min_y..=max_y).contains(&cell.y) {
+                cell.die();
             }
-            cells.push(row);
         }
+        self.revision += 1;
+    }
 
-        Self::with_cells(cells)
+    /// Revives each cell with probability `density`, killing the rest, with
+    /// a seed taken from the clock. Returns the seed, which fills the board
+    /// the same way again with `randomize_with_seed`.
+    pub fn randomize(&mut self, density: f64) -> u64 {
+        let seed = rng::seed_from_time();
+        self.randomize_with_seed(seed, density);
+        seed
     }
 
-    pub fn cells(&self) -> Vec<(&Cell, (u16, u16))> {
-        let mut result = vec![];
-        for (y, row) in self.cells.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                result.push((cell, (x as u16, y as u16)))
+    /// Like `randomize`, but the same `seed` and `density` always give the
+    /// same board for the same size.
+    pub fn randomize_with_seed(&mut self, seed: u64, density: f64) {
+        let mut rng = Rng::new(seed);
+        for cell in self.cells.iter_mut().flatten() {
+            if rng.next_f64() < density {
+                cell.live();
+            } else {
+                cell.die();
             }
         }
-        result
+        self.revision += 1;
     }
 
-    pub fn revive_cell_at_pos(&mut self, x: u16, y: u16) -> Option<()> {
-        let cell = self.find_cell_at_pos_mut(x, y)?;
-        cell.live();
-        Some(())
+    /// Makes every other cell alive, like the black squares of a chessboard.
+    pub fn fill_checkerboard(&mut self) {
+        for cell in self.cells.iter_mut().flatten() {
+            if (cell.x + cell.y) % 2 == 0 {
+                cell.live();
+            } else {
+                cell.die();
+            }
+        }
+        self.revision += 1;
     }
 
-    pub fn kill_cell_at_pos(&mut self, x: u16, y: u16) -> Option<()> {
-        let cell = self.find_cell_at_pos_mut(x, y)?;
-        cell.die();
-        Some(())
+    /// Kills every live cell and revives every other one, decaying cells
+    /// included, for the negative of the board.
+    pub fn invert(&mut self) {
+        for cell in self.cells.iter_mut().flatten() {
+            if cell.is_alive() {
+                cell.die();
+            } else {
+                cell.live();
+            }
+        }
+        self.revision += 1;
     }
 
-    pub fn resize_if_larger(&mut self, width: u16, height: u16) {
-        let old_height = self.cells.len() as u16;
-        let old_width = self.cells[0].len() as u16;
-        if height > old_height {
-            for y in old_height..height {
-                let mut row = vec![];
-                for x in 0..old_width {
-                    let cell = Cell::new(x, y);
-                    row.push(cell);
+    /// Counts the places where `pattern` appears on its own: its live cells
+    /// are alive, and every other cell in its bounding box and in a one cell
+    /// border around it is dead, so a match isn't part of something larger.
+    pub fn count_pattern(&self, pattern: &Pattern) -> usize {
+        let (width, height) = self.size();
+        let (pattern_width, pattern_height) = pattern.size();
+        if pattern.cells().is_empty() || pattern_width > width || pattern_height > height {
+            return 0;
+        }
+
+        let mut count = 0;
+        for top in 0..=height - pattern_height {
+            for left in 0..=width - pattern_width {
+                if self.matches_at(pattern, left, top) {
+                    count += 1;
                 }
-                self.cells.push(row);
             }
         }
+        count
+    }
 
-        if width > old_width {
-            for (y, row) in self.cells.iter_mut().enumerate() {
-                for x in old_width..width {
-                    let cell = Cell::new(x, y as u16);
-                    row.push(cell);
+    fn matches_at(&self, pattern: &Pattern, left: u16, top: u16) -> bool {
+        let (pattern_width, pattern_height) = pattern.size();
+        for dy in -1..=i32::from(pattern_height) {
+            for dx in -1..=i32::from(pattern_width) {
+                let x = i32::from(left) + dx;
+                let y = i32::from(top) + dy;
+                let alive = x >= 0
+                    && y >= 0
+                    && self
+                        .find_cell_at_pos(x as u16, y as u16)
+                        .is_some_and(Cell::is_alive);
+                let expected = dx >= 0 && dy >= 0 && pattern.is_alive(dx as u16, dy as u16);
+                if alive != expected {
+                    return false;
                 }
             }
         }
+        true
+    }
+
+    /// How many cells, dead or alive, have each number of live neighbours,
+    /// indexed by that number. Shows what a rule has to work with, like why
+    /// it explodes or dies out.
+    pub fn neighbour_histogram(&self) -> [usize; 9] {
+        let mut histogram = [0; 9];
+        for cell in self.cells.iter().flatten() {
+            histogram[usize::from(self.get_neighbours_count_at_pos(cell.x, cell.y))] += 1;
+        }
+        histogram
+    }
+
+    /// Smallest rectangle containing every live cell, as the inclusive
+    /// `(min_x, min_y, max_x, max_y)`, or `None` if there are no live cells.
+    pub fn live_bounds(&self) -> Option<(u16, u16, u16, u16)> {
+        self.live_cells().fold(None, |bounds, (x, y)| match bounds {
+            None => Some((x, y, x, y)),
+            Some((min_x, min_y, max_x, max_y)) => {
+                Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)))
+            }
+        })
+    }
+
+    /// Width and height of the board.
+    pub fn size(&self) -> (u16, u16) {
+        (self.width(), self.height())
+    }
+
+    /// Number of columns
+    pub fn width(&self) -> u16 {
+        self.cells.first().map_or(0, |row| row.len() as u16)
+    }
+
+    /// Number of rows
+    pub fn height(&self) -> u16 {
+        self.cells.len() as u16
+    }
+
+    /// Writes 1 for each live cell and 0 for each other one into `buf`, row
+    /// by row, without allocating. Meant for frontends that draw the board
+    /// themselves, like a web page given the buffer from WebAssembly.
+    ///
+    /// # Panics
+    ///
+    /// If `buf` isn't `width * height` bytes long.
+    pub fn render_into(&self, buf: &mut [u8]) {
+        assert_eq!(
+            buf.len(),
+            usize::from(self.width()) * usize::from(self.height()),
+            "buffer length doesn't match the board size"
+        );
+        for (byte, cell) in buf.iter_mut().zip(self.cells.iter().flatten()) {
+            *byte = u8::from(cell.is_alive());
+        }
+    }
+
+    /// Sets the B/S rule, replacing any custom one.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.custom_rule = None;
+    }
+
+    /// The B/S rule, which isn't used while there's a custom one
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// Makes ticks follow `rule` instead of the B/S rule, until `set_rule`
+    /// is called. Cells die right away, as with two-state rules.
+    pub fn set_custom_rule(&mut self, rule: impl TransitionRule + 'static) {
+        self.custom_rule = Some(Box::new(rule));
+    }
+
+    /// The rule given with `set_custom_rule`, if any
+    pub fn custom_rule(&self) -> Option<&dyn TransitionRule> {
+        self.custom_rule.as_deref()
+    }
+
+    /// What happens at the edges of the board, `Bounded` by default.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// What happens at the edges of the board
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Which cells count as neighbours for the rule, the Moore neighbourhood
+    /// by default.
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) {
+        self.neighbourhood = neighbourhood;
+    }
+
+    /// Which cells count as neighbours
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    /// Moves every cell by `dx` columns and `dy` rows. Cells pushed off the
+    /// board are dropped.
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        let (width, height) = self.size();
+        let mut cells = dead_cells(width, height);
+        for cell in self.cells.iter().flatten() {
+            if cell.kind == CellKind::Dead {
+                continue;
+            }
+            let x = i32::from(cell.x) + dx;
+            let y = i32::from(cell.y) + dy;
+            if (0..i32::from(width)).contains(&x) && (0..i32::from(height)).contains(&y) {
+                let moved = &mut cells[y as usize][x as usize];
+                moved.kind = cell.kind;
+                moved.age = cell.age;
+                moved.color = cell.color;
+            }
+        }
+        self.cells = cells;
+        self.revision += 1;
+    }
+
+    /// Resizes the board to `width` x `height`. Cells beyond the new size are
+    /// dropped, so shrinking and growing back doesn't bring them back, unless
+    /// `preserve_on_resize` is set: then the board only ever grows.
+    ///
+    /// Returns false, leaving the board as it is, if it would have more than
+    /// `max_cells` cells.
+    pub fn resize(&mut self, width: u16, height: u16) -> bool {
+        if self.preserve_on_resize {
+            return self.resize_if_larger(width, height);
+        }
+        if !self.fits(width, height) {
+            return false;
+        }
+        if width == 0 || height == 0 {
+            self.cells.clear();
+            self.revision += 1;
+            return true;
+        }
+        self.cells.truncate(height as usize);
+        for row in self.cells.iter_mut() {
+            row.truncate(width as usize);
+        }
+        self.resize_if_larger(width, height)
+    }
+
+    /// Grows the board to at least `width` x `height`, adding dead cells to
+    /// the right and bottom. Rows added along with columns get the new width
+    /// too, so the board stays rectangular.
+    ///
+    /// Returns false, leaving the board as it is, if it would have more than
+    /// `max_cells` cells.
+    pub fn resize_if_larger(&mut self, width: u16, height: u16) -> bool {
+        let old_height = self.height();
+        let width = width.max(self.width());
+        if !self.fits(width, height.max(old_height)) {
+            return false;
+        }
+        // Rows without cells would be a board 0 wide but not 0 high
+        if width == 0 || height.max(old_height) == 0 {
+            return true;
+        }
+        self.revision += 1;
+        for (y, row) in self.cells.iter_mut().enumerate() {
+            for x in row.len() as u16..width {
+                row.push(Cell::dead(x, y as u16));
+            }
+        }
+        for y in old_height..height {
+            self.cells
+                .push((0..width).map(|x| Cell::dead(x, y)).collect());
+        }
+        true
     }
 
+    /// Moves on to the next generation, growing the board first if it
+    /// auto-grows.
     pub fn tick(&mut self) {
-        let mut new_cells = vec![];
-
-        for (y, row) in self.cells.iter().enumerate() {
-            let mut new_row = vec![];
-            for (x, cell) in row.iter().enumerate() {
-                let x = x as u16;
-                let y = y as u16;
-                let neighbours_count = self.get_neighbours_count_at_pos(x, y);
-                match (cell.is_alive(), neighbours_count) {
-                    (true, 2..=3) | (false, 3) => {
-                        let new_cell = Cell::new_alive(x, y);
-                        new_row.push(new_cell);
-                    }
-                    (true, _) | (false, _) => {
-                        let new_cell = Cell::new(x, y);
-                        new_row.push(new_cell);
-                    }
+        if self.auto_grow && self.topology == Topology::Bounded {
+            self.grow_around_live_cells();
+        }
+
+        // Shaped like the board again in case it was resized since
+        let mut next = std::mem::take(&mut self.next);
+        next.resize_with(self.cells.len(), Vec::new);
+        for (row, next_row) in self.cells.iter().zip(&mut next) {
+            next_row.clear();
+            next_row.extend(row.iter().map(|cell| {
+                cell.next(self.next_kind(cell), || {
+                    self.inherited_color(cell.x, cell.y)
+                })
+            }));
+        }
+
+        std::mem::swap(&mut self.cells, &mut next);
+        self.next = next;
+        self.mutate();
+        self.revision += 1;
+        self.generation += 1;
+    }
+
+    /// Flips each cell with probability `rate` after every tick, from 0 (the
+    /// default, no mutations) to 1, which inverts the board the rule left.
+    /// Boards that would die out or settle keep changing then.
+    pub fn set_mutation_rate(&mut self, rate: f64) {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+    }
+
+    /// Chance of each cell flipping after every tick
+    pub fn mutation_rate(&self) -> f64 {
+        self.mutation_rate
+    }
+
+    /// Seeds the mutations, which are seeded from the clock otherwise, so
+    /// the same seed mutates the same way again
+    pub fn set_mutation_seed(&mut self, seed: u64) {
+        self.mutation_rng = Rng::new(seed);
+    }
+
+    fn mutate(&mut self) {
+        if self.mutation_rate == 0.0 {
+            return;
+        }
+        let (width, height) = self.size();
+        for cell in self.cells.iter_mut().flatten() {
+            // Dead borders stay dead, whatever happens inside
+            if self.border_dead && cell.on_border(width, height) {
+                continue;
+            }
+            if self.mutation_rng.next_f64() < self.mutation_rate {
+                if cell.is_alive() {
+                    cell.die();
+                } else {
+                    cell.live();
                 }
             }
-            new_cells.push(new_row);
         }
+    }
 
-        self.cells = new_cells
+    /// Ticks until the generation reaches `target`, calling `progress` with
+    /// the generation after each tick. Does nothing if `target` is behind.
+    pub fn tick_to(&mut self, target: u64, mut progress: impl FnMut(u64)) {
+        while self.generation < target {
+            self.tick();
+            progress(self.generation);
+        }
     }
 
-    fn with_cells(cells: Vec<Vec<Cell>>) -> Self {
-        Self { cells }
+    /// Whether the next tick would leave every cell as it is, like for still
+    /// lifes and empty boards. Oscillators aren't stable, and neither is any
+    /// board with mutations.
+    pub fn is_stable(&self) -> bool {
+        self.mutation_rate == 0.0
+            && self
+                .cells
+                .iter()
+                .flatten()
+                .all(|cell| self.next_kind(cell) == cell.kind)
     }
 
-    fn find_cell_at_pos_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
-        let x = x as usize;
-        let y = y as usize;
-        let row = self.cells.get_mut(y)?;
-        row.get_mut(x)
+    /// Positions of the cells the next tick gives birth to and of the live
+    /// ones it kills, decaying counting as dying, from the neighbours they
+    /// have now. Leaves out auto-growing and mutations.
+    pub fn changes_next_gen(&self) -> (Vec<Position>, Vec<Position>) {
+        let (mut births, mut deaths) = (vec![], vec![]);
+        for cell in self.cells.iter().flatten() {
+            let alive_next = self.next_kind(cell) == CellKind::Alive;
+            if !cell.is_alive() && alive_next {
+                births.push((cell.x, cell.y));
+            } else if cell.is_alive() && !alive_next {
+                deaths.push((cell.x, cell.y));
+            }
+        }
+        (births, deaths)
     }
 
-    fn get_neighbours_count_at_pos(&self, x: u16, y: u16) -> u8 {
-        let prev_y = y.saturating_sub(1) as usize;
-        let current_y = y as usize;
-        let next_y = y.saturating_add(1) as usize;
-        let prev_x = x.saturating_sub(1) as usize;
-        let next_x = x.saturating_add(1) as usize;
-        let until = next_x - prev_x + 1;
-        let filter = |c: &Cell| c.is_alive();
-
-        let prev_count = if prev_y != current_y {
-            slice_len_2d(&self.cells, prev_y, prev_x, until, filter)
-        } else {
-            0
-        };
-        let current_count = slice_len_2d(&self.cells, current_y, prev_x, until, |c| {
-            c.is_alive() && (c.x, c.y) != (x, y)
-        });
-        let next_count = slice_len_2d(&self.cells, next_y, prev_x, until, filter);
+    /// Number of generations after which the board repeats itself, looking
+    /// at most `max_generations` ahead. Boards that settle into an oscillator
+    /// after a while get the oscillator's period, still lifes a period of 1.
+    ///
+    /// States are compared by the whole grid, so on a wrapping board a
+    /// spaceship coming back to where it started counts as a repeat. Every
+    /// state is kept until one repeats, so a hash collision can't pass for a
+    /// repeat. The board is left as it was.
+    ///
+    /// Mutations are left out, a board that keeps mutating would never
+    /// repeat, and their random numbers are kept for the real ticks.
+    pub fn detect_period(&mut self, max_generations: u64) -> Option<u64> {
+        let cells = self.cells.clone();
+        let (origin, revision, generation) = (self.origin, self.revision, self.generation);
+        let mutation_rate = std::mem::replace(&mut self.mutation_rate, 0.0);
+
+        let mut seen = HashMap::new();
+        let mut period = None;
+        for step in 0..=max_generations {
+            if let Some(first) = seen.insert(self.state(), step) {
+                period = Some(step - first);
+                break;
+            }
+            self.tick();
+        }
 
-        (prev_count + current_count + next_count) as u8
+        self.cells = cells;
+        self.origin = origin;
+        self.revision = revision;
+        self.generation = generation;
+        self.mutation_rate = mutation_rate;
+        period
     }
-}
 
-fn slice_until<T: std::fmt::Debug>(a: &[T], from: usize, until: usize) -> Option<&[T]> {
-    a.get(from..).and_then(|a| {
-        let until = until.min(a.len());
-        a.get(..until)
-    })
-}
+    /// The size and the state of every cell, ignoring ages
+    fn state(&self) -> ((u16, u16), Vec<CellKind>) {
+        let kinds = self.cells.iter().flatten().map(|cell| cell.kind).collect();
+        (self.size(), kinds)
+    }
 
-fn slice_len_2d<T, F>(
-    a: &[Vec<T>],
-    y_index: usize,
-    from: usize,
-    until: usize,
-    mut filter: F,
-) -> usize
-where
-    T: std::fmt::Debug,
-    F: FnMut(&T) -> bool,
-{
-    a.get(y_index)
-        .and_then(|slice| slice_until(slice, from, until))
-        .map_or(0, |slice| slice.iter().filter(|t| filter(*t)).count())
-}
+    fn next_kind(&self, cell: &Cell) -> CellKind {
+        let (width, height) = self.size();
+        if self.border_dead && cell.on_border(width, height) {
+            return CellKind::Dead;
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let neighbours_count = self.get_neighbours_count_at_pos(cell.x, cell.y);
+        if let Some(rule) = &self.custom_rule {
+            return if rule.next(cell.kind == CellKind::Alive, neighbours_count) {
+                CellKind::Alive
+            } else {
+                CellKind::Dead
+            };
+        }
+        match cell.kind {
+            CellKind::Alive if self.rule.survives(neighbours_count) => CellKind::Alive,
+            CellKind::Alive if self.rule.states() > 2 => CellKind::Dying(self.rule.states() - 1),
+            CellKind::Dead if self.rule.born(neighbours_count) => CellKind::Alive,
+            CellKind::Dying(left) if left > 2 => CellKind::Dying(left - 1),
+            CellKind::Alive | CellKind::Dying(_) | CellKind::Dead => CellKind::Dead,
+        }
+    }
 
-    #[test]
-    fn it_resizes_if_larger() {
-        let mut game = Game {
-            cells: vec![
-                vec![Cell::new(0, 0), Cell::new(1, 0), Cell::new(2, 0)],
-                vec![Cell::new(0, 1), Cell::new(1, 1), Cell::new(2, 1)],
-            ],
+    fn with_cells(cells: Vec<Vec<Cell>>) -> Self {
+        Self {
+            cells,
+            next: vec![],
+            auto_grow: false,
+            border_dead: false,
+            preserve_on_resize: false,
+            max_size: None,
+            max_cells: DEFAULT_MAX_CELLS,
+            origin: (0, 0),
+            rule: Rule::default(),
+            custom_rule: None,
+            topology: Topology::Bounded,
+            neighbourhood: Neighbourhood::Moore,
+            revision: 0,
+            generation: 0,
+            mutation_rate: 0.0,
+            mutation_rng: Rng::new(rng::seed_from_time()),
+        }
+    }
+
+    fn grow_around_live_cells(&mut self) {
+        let (width, height) = (self.width(), self.height());
+        let (mut left, mut top, mut right, mut bottom) = (false, false, false, false);
+        for (x, y) in self.live_cells() {
+            left |= x <= 1;
+            top |= y <= 1;
+            right |= x >= width.saturating_sub(2);
+            bottom |= y >= height.saturating_sub(2);
+        }
+
+        let (max_width, max_height) = self.max_size.unwrap_or((u16::MAX, u16::MAX));
+        let mut width_room = max_width.saturating_sub(width);
+        let mut height_room = max_height.saturating_sub(height);
+        let take = |needed: bool, room: &mut u16| {
+            let amount = if needed { GROW_CHUNK.min(*room) } else { 0 };
+            *room -= amount;
+            amount
         };
+        let left = take(left, &mut width_room);
+        let right = take(right, &mut width_room);
+        let top = take(top, &mut height_room);
+        let bottom = take(bottom, &mut height_room);
 
-        game.resize_if_larger(4, 3);
+        let fits = self.fits(width + left + right, height + top + bottom);
+        if left + top + right + bottom > 0 && fits {
+            self.grow(left, top, right, bottom);
+        }
+    }
 
-        assert_eq!(
-            game.cells,
-            vec![
-                vec![
-                    Cell::new(0, 0),
-                    Cell::new(1, 0),
-                    Cell::new(2, 0),
-                    Cell::new(3, 0)
-                ],
-                vec![
-                    Cell::new(0, 1),
-                    Cell::new(1, 1),
-                    Cell::new(2, 1),
-                    Cell::new(3, 1)
-                ],
-                vec![
-                    Cell::new(0, 2),
-                    Cell::new(1, 2),
-                    Cell::new(2, 2),
-                    Cell::new(3, 2)
-                ]
-            ]
-        )
+    fn grow(&mut self, left: u16, top: u16, right: u16, bottom: u16) {
+        let width = self.width() + left + right;
+        let old_height = self.height();
+        let dead_row = |y: u16| (0..width).map(|x| Cell::dead(x, y)).collect::<Vec<_>>();
+
+        let mut cells = vec![];
+        for y in 0..top {
+            cells.push(dead_row(y));
+        }
+        for (y, old_row) in std::mem::take(&mut self.cells).into_iter().enumerate() {
+            let y = y as u16 + top;
+            let mut row: Vec<Cell> = (0..left).map(|x| Cell::dead(x, y)).collect();
+            for mut cell in old_row {
+                cell.x += left;
+                cell.y = y;
+                row.push(cell);
+            }
+            let old_width = row.len() as u16;
+            row.extend((old_width..width).map(|x| Cell::dead(x, y)));
+            cells.push(row);
+        }
+        for y in top + old_height..top + old_height + bottom {
+            cells.push(dead_row(y));
+        }
+
+        self.cells = cells;
+        self.origin = (self.origin.0 + left, self.origin.1 + top);
+        self.revision += 1;
+    }
+
+    /// The cell at `(x, y)`, or `None` off the board
+    pub fn find_cell_at_pos(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.cells.get(y as usize)?.get(x as usize)
+    }
+
+    /// Puts `cell` on the board at its position, in place of the one there,
+    /// to build boards from cells made with `Cell::alive` and `Cell::dead`.
+    pub fn set_cell(&mut self, cell: Cell) -> Result<(), GameError> {
+        let (x, y) = cell.position();
+        *self.cell_at_pos_mut(x, y)? = cell;
+        Ok(())
+    }
+
+    /// Same as `find_cell_at_pos`, by a shorter name
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.find_cell_at_pos(x, y)
+    }
+
+    fn cell_at_pos_mut(&mut self, x: u16, y: u16) -> Result<&mut Cell, GameError> {
+        let (width, height) = self.size();
+        let cell = self
+            .cells
+            .get_mut(y as usize)
+            .and_then(|row| row.get_mut(x as usize))
+            .ok_or(GameError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            })?;
+        // Only once it's found, missing cells change nothing
+        self.revision += 1;
+        Ok(cell)
+    }
+
+    /// Colour a cell born at `(x, y)` gets from its parents: the one most of
+    /// its coloured live neighbours have, the first one found on a tie, or
+    /// none if none of them are coloured.
+    fn inherited_color(&self, x: u16, y: u16) -> Option<Color> {
+        let mut counts: Vec<(Color, u8)> = vec![];
+        for (neighbour_x, neighbour_y) in self.neighbour_positions(x, y) {
+            let neighbour = &self.cells[neighbour_y][neighbour_x];
+            if let Some(color) = neighbour.color.filter(|_| neighbour.is_alive()) {
+                match counts.iter_mut().find(|(counted, _)| *counted == color) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((color, 1)),
+                }
+            }
+        }
+        counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|&(color, _)| color)
+    }
+
+    /// Positions of the cells around `(x, y)` in the neighbourhood that are
+    /// on the board, or wrap or mirror back onto it
+    fn neighbour_positions(&self, x: u16, y: u16) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbourhood
+            .offsets()
+            .iter()
+            .filter_map(move |&(dx, dy)| {
+                self.topology
+                    .wrap(i32::from(x) + dx, self.width())
+                    .zip(self.topology.wrap(i32::from(y) + dy, self.height()))
+            })
+    }
+
+    fn get_neighbours_count_at_pos(&self, x: u16, y: u16) -> u8 {
+        self.neighbour_positions(x, y)
+            .filter(|&(x, y)| self.cells[y][x].is_alive())
+            .count() as u8
+    }
+}
+
+/// Sets up a `Game` with more than its size, checking the settings go
+/// together before building it:
+///
+/// ```
+/// use gameoflife::{Game, Pattern, Rule, Topology};
+///
+/// let game = Game::builder(40, 20)
+///     .topology(Topology::Toroidal)
+///     .rule("B36/S23".parse::<Rule>()?)
+///     .pattern(Pattern::preset("glider").unwrap())
+///     .build()?;
+/// assert_eq!(game.population(), 5);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameBuilder {
+    width: u16,
+    height: u16,
+    topology: Topology,
+    neighbourhood: Neighbourhood,
+    rule: Rule,
+    pattern: Option<Pattern>,
+    density: Option<f64>,
+    seed: Option<u64>,
+    mutation_rate: f64,
+}
+
+impl GameBuilder {
+    /// A builder for an empty board with the defaults of `Game::new`.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            topology: Topology::Bounded,
+            neighbourhood: Neighbourhood::Moore,
+            rule: Rule::default(),
+            pattern: None,
+            density: None,
+            seed: None,
+            mutation_rate: 0.0,
+        }
+    }
+
+    /// Sets the size of the board.
+    pub fn size(mut self, width: u16, height: u16) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// What happens at the edges of the board.
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Which cells count as neighbours for the rule.
+    pub fn neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// The B/S rule the board ticks by.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Stamps `pattern` in the middle of the board, over the random cells
+    /// if there are any. It has to fit.
+    pub fn pattern(mut self, pattern: Pattern) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Revives each cell with probability `density`, between 0 and 1.
+    pub fn density(mut self, density: f64) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    /// Fills the board the same way every time with `density`, and makes
+    /// mutations repeat too. Without it, the seeds come from the clock.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Chance of each cell flipping after every tick, between 0 and 1.
+    pub fn mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate;
+        self
+    }
+
+    /// Builds the game, or errors with `GameError::InvalidSize` for a board
+    /// with more than `DEFAULT_MAX_CELLS` cells, `GameError::InvalidDensity`
+    /// for a density or mutation rate outside `0..=1` and
+    /// `GameError::PatternTooLarge` for a pattern that doesn't fit.
+    pub fn build(self) -> Result<Game, GameError> {
+        let (width, height) = (self.width, self.height);
+        if usize::from(width) * usize::from(height) > DEFAULT_MAX_CELLS {
+            return Err(GameError::InvalidSize {
+                width,
+                height,
+                max_cells: DEFAULT_MAX_CELLS,
+            });
+        }
+        for &chance in self.density.iter().chain([&self.mutation_rate]) {
+            if !(0.0..=1.0).contains(&chance) {
+                return Err(GameError::InvalidDensity(chance));
+            }
+        }
+        if let Some(pattern) = &self.pattern {
+            let (pattern_width, pattern_height) = pattern.size();
+            if pattern_width > width || pattern_height > height {
+                return Err(GameError::PatternTooLarge {
+                    pattern_width,
+                    pattern_height,
+                    width,
+                    height,
+                });
+            }
+        }
+
+        let mut game = Game::with_cells(dead_cells(width, height));
+        game.set_rule(self.rule);
+        game.set_topology(self.topology);
+        game.set_neighbourhood(self.neighbourhood);
+        game.set_mutation_rate(self.mutation_rate);
+        if let Some(seed) = self.seed {
+            game.set_mutation_seed(seed);
+        }
+        if let Some(density) = self.density {
+            game.randomize_with_seed(self.seed.unwrap_or_else(rng::seed_from_time), density);
+        }
+        if let Some(pattern) = &self.pattern {
+            game.stamp_centered(pattern);
+        }
+        Ok(game)
+    }
+}
+
+fn dead_cells(width: u16, height: u16) -> Vec<Vec<Cell>> {
+    let mut cells = vec![];
+    if width == 0 {
+        return cells;
+    }
+    for y in 0..height {
+        let mut row = vec![];
+        for x in 0..width {
+            let cell = Cell::dead(x, y);
+            row.push(cell);
+        }
+        cells.push(row);
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_resizes_if_larger() {
+        let mut game = Game::with_cells(vec![
+            vec![Cell::dead(0, 0), Cell::dead(1, 0), Cell::dead(2, 0)],
+            vec![Cell::dead(0, 1), Cell::dead(1, 1), Cell::dead(2, 1)],
+        ]);
+
+        game.resize_if_larger(4, 3);
+
+        assert_eq!(
+            game.cells,
+            vec![
+                vec![
+                    Cell::dead(0, 0),
+                    Cell::dead(1, 0),
+                    Cell::dead(2, 0),
+                    Cell::dead(3, 0)
+                ],
+                vec![
+                    Cell::dead(0, 1),
+                    Cell::dead(1, 1),
+                    Cell::dead(2, 1),
+                    Cell::dead(3, 1)
+                ],
+                vec![
+                    Cell::dead(0, 2),
+                    Cell::dead(1, 2),
+                    Cell::dead(2, 2),
+                    Cell::dead(3, 2)
+                ]
+            ]
+        )
+    }
+
+    fn live_positions(game: &Game) -> Vec<(u16, u16)> {
+        game.live_cells().collect()
+    }
+
+    #[test]
+    fn it_truncates_when_resizing_to_a_smaller_size() {
+        let mut game = Game::new(6, 6);
+        // Right on the new edges, kept
+        game.revive_cell_at_pos(3, 0).unwrap();
+        game.revive_cell_at_pos(0, 3).unwrap();
+        game.revive_cell_at_pos(3, 3).unwrap();
+        // Just past them, dropped
+        game.revive_cell_at_pos(4, 2).unwrap();
+        game.revive_cell_at_pos(2, 4).unwrap();
+        game.revive_cell_at_pos(5, 5).unwrap();
+
+        game.resize(4, 4);
+
+        assert_eq!(game.size(), (4, 4));
+        assert_eq!(live_positions(&game), vec![(3, 0), (0, 3), (3, 3)]);
+        assert_eq!(game.population(), 3);
+
+        game.resize(6, 6);
+
+        assert_eq!(game.size(), (6, 6));
+        assert_eq!(game.population(), 3);
+        for (cell, x, y) in game.iter_cells() {
+            assert_eq!((cell.x, cell.y), (x, y));
+        }
+    }
+
+    #[test]
+    fn it_translates_cells() {
+        let mut game = Game::new(5, 5);
+        game.revive_cell_at_pos(0, 0).unwrap();
+        game.revive_cell_at_pos(1, 0).unwrap();
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(1, 1).unwrap();
+        let red = Color { r: 255, g: 0, b: 0 };
+        game.set_cell_color(1, 1, Some(red)).unwrap();
+
+        game.translate(2, 1);
+
+        assert_eq!(live_positions(&game), vec![(2, 1), (3, 1), (2, 2), (3, 2)]);
+        let color_at = |x, y| game.find_cell_at_pos(x, y).unwrap().color();
+        assert_eq!(color_at(3, 2), Some(red));
+        assert_eq!(color_at(2, 2), None);
+
+        game.translate(2, -1);
+
+        // The right column went off the board
+        assert_eq!(live_positions(&game), vec![(4, 0), (4, 1)]);
+    }
+
+    #[test]
+    fn it_keeps_cells_when_resizing_only_if_larger() {
+        let mut game = Game::new(6, 6);
+        game.revive_cell_at_pos(5, 5).unwrap();
+
+        game.resize_if_larger(4, 4);
+
+        assert_eq!(game.size(), (6, 6));
+        assert_eq!(game.population(), 1);
+    }
+
+    #[test]
+    fn it_auto_grows_around_cells_near_the_edge() {
+        let mut game = Game::new(5, 5);
+        game.set_auto_grow(true);
+        // Vertical blinker touching the top-left corner
+        game.revive_cell_at_pos(0, 0).unwrap();
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(0, 2).unwrap();
+
+        game.tick();
+
+        assert_eq!(game.origin(), (GROW_CHUNK, GROW_CHUNK));
+        assert_eq!(game.width(), 5 + GROW_CHUNK);
+        assert_eq!(game.height(), 5 + GROW_CHUNK);
+        let (x, y) = game.origin();
+        assert_eq!(
+            live_positions(&game),
+            vec![(x - 1, y + 1), (x, y + 1), (x + 1, y + 1)]
+        );
+        for (cell, x, y) in game.iter_cells() {
+            assert_eq!((cell.x, cell.y), (x, y));
+        }
+    }
+
+    #[test]
+    fn it_does_not_auto_grow_past_the_max_size() {
+        let mut game = Game::new(5, 5);
+        game.set_auto_grow(true);
+        game.set_max_size(Some((8, 5)));
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(0, 2).unwrap();
+        game.revive_cell_at_pos(0, 3).unwrap();
+
+        game.tick();
+
+        assert_eq!((game.width(), game.height()), (8, 5));
+        assert_eq!(game.origin(), (3, 0));
+    }
+
+    #[test]
+    fn it_does_not_overflow_growing_the_largest_board() {
+        let mut game = Game::new(u16::MAX, 1);
+        game.set_auto_grow(true);
+        game.set_max_size(Some((u16::MAX, 1)));
+        game.revive_cell_at_pos(u16::MAX - 1, 0).unwrap();
+
+        game.tick();
+
+        assert_eq!((game.width(), game.height()), (u16::MAX, 1));
+    }
+
+    #[test]
+    fn it_does_not_grow_past_the_max_cells() {
+        let mut game = Game::new(5, 5);
+        game.set_max_cells(30);
+        game.revive_cell_at_pos(2, 2).unwrap();
+
+        assert!(!game.resize(8, 4));
+        assert!(!game.resize_if_larger(5, 7));
+        assert_eq!((game.width(), game.height()), (5, 5));
+        assert_eq!(live_positions(&game), [(2, 2)]);
+        assert!(game.resize(6, 5));
+        assert_eq!((game.width(), game.height()), (6, 5));
+
+        game.set_auto_grow(true);
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(0, 2).unwrap();
+        game.revive_cell_at_pos(0, 3).unwrap();
+        game.tick();
+        assert_eq!((game.width(), game.height()), (6, 5));
+    }
+
+    #[test]
+    fn it_does_not_grow_when_auto_grow_is_off() {
+        let mut game = Game::new(5, 5);
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(0, 2).unwrap();
+        game.revive_cell_at_pos(0, 3).unwrap();
+
+        game.tick();
+
+        assert_eq!((game.width(), game.height()), (5, 5));
+        assert_eq!(live_positions(&game), vec![(0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn it_builds_a_game_with_every_setting() {
+        let rule: Rule = "B36/S23".parse().unwrap();
+        let glider = Pattern::preset("glider").unwrap();
+        let game = Game::builder(1, 1)
+            .size(20, 10)
+            .topology(Topology::Toroidal)
+            .neighbourhood(Neighbourhood::VonNeumann)
+            .rule(rule.clone())
+            .density(0.5)
+            .seed(7)
+            .mutation_rate(0.01)
+            .pattern(glider.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!((game.width(), game.height()), (20, 10));
+        assert_eq!(game.topology(), Topology::Toroidal);
+        assert_eq!(game.neighbourhood(), Neighbourhood::VonNeumann);
+        assert_eq!(game.rule(), &rule);
+        assert_eq!(game.mutation_rate(), 0.01);
+        // The pattern goes over the random cells, in the middle
+        assert!(glider
+            .cells()
+            .iter()
+            .all(|&(x, y)| game.get(8 + x, 3 + y).is_some_and(Cell::is_alive)));
+
+        let mut same = Game::new(20, 10);
+        same.randomize_with_seed(7, 0.5);
+        same.stamp_centered(&glider);
+        assert_eq!(live_positions(&game), live_positions(&same));
+    }
+
+    #[test]
+    fn it_refuses_boards_too_large_to_build() {
+        let error = Game::builder(u16::MAX, u16::MAX).build().err().unwrap();
+
+        assert!(matches!(
+            error,
+            GameError::InvalidSize {
+                width: u16::MAX,
+                height: u16::MAX,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_refuses_densities_outside_zero_to_one() {
+        for density in [-0.1, 1.5, f64::NAN] {
+            let error = Game::builder(5, 5).density(density).build().err();
+            assert!(matches!(error, Some(GameError::InvalidDensity(_))));
+        }
+        let error = Game::builder(5, 5).mutation_rate(2.0).build().err();
+        assert!(matches!(error, Some(GameError::InvalidDensity(_))));
+
+        assert!(Game::builder(5, 5).density(1.0).build().is_ok());
+    }
+
+    #[test]
+    fn it_refuses_patterns_larger_than_the_board() {
+        let glider = Pattern::preset("glider").unwrap();
+
+        let error = Game::builder(3, 2).pattern(glider.clone()).build().err();
+
+        assert!(matches!(
+            error,
+            Some(GameError::PatternTooLarge {
+                pattern_width: 3,
+                pattern_height: 3,
+                width: 3,
+                height: 2,
+            })
+        ));
+        assert_eq!(
+            Game::builder(3, 3)
+                .pattern(glider)
+                .build()
+                .unwrap()
+                .population(),
+            5
+        );
+    }
+
+    #[test]
+    fn it_counts_neighbours_in_either_neighbourhood() {
+        let mut game = Game::new(3, 3);
+        game.stamp(&Pattern::from_rows(&[".O.", "O.O", ".O."]), 0, 0);
+        game.set_neighbourhood(Neighbourhood::VonNeumann);
+        assert_eq!(game.get_neighbours_count_at_pos(1, 1), 4);
+
+        // Diagonals only count in the Moore neighbourhood
+        game.stamp(&Pattern::from_rows(&["O.O", "...", "O.O"]), 0, 0);
+        assert_eq!(game.get_neighbours_count_at_pos(1, 1), 4);
+        game.set_neighbourhood(Neighbourhood::Moore);
+        assert_eq!(game.get_neighbours_count_at_pos(1, 1), 8);
+    }
+
+    #[test]
+    fn it_passes_colours_on_to_newborn_cells() {
+        let red = Color { r: 255, g: 0, b: 0 };
+        let blue = Color { r: 0, g: 0, b: 255 };
+        let mut game = Game::new(5, 5);
+        game.stamp(&Pattern::preset("blinker").unwrap(), 1, 2);
+        game.set_cell_color(1, 2, Some(blue)).unwrap();
+        game.set_cell_color(2, 2, Some(red)).unwrap();
+        game.set_cell_color(3, 2, Some(red)).unwrap();
+
+        game.tick();
+
+        let color_at = |x, y| game.find_cell_at_pos(x, y).unwrap().color();
+        // Born with the colour two of their three parents have
+        assert_eq!(color_at(2, 1), Some(red));
+        assert_eq!(color_at(2, 3), Some(red));
+        // Survivors keep theirs, and the dead lose it
+        assert_eq!(color_at(2, 2), Some(red));
+        assert_eq!(color_at(1, 2), None);
+
+        let mut plain = Game::new(5, 5);
+        plain.stamp(&Pattern::preset("blinker").unwrap(), 1, 2);
+        plain.tick();
+        assert!(plain
+            .iter_cells()
+            .all(|(cell, _, _)| cell.color().is_none()));
+    }
+
+    #[test]
+    fn it_follows_a_custom_rule() {
+        struct Extinction;
+
+        impl TransitionRule for Extinction {
+            fn next(&self, _alive: bool, _neighbours: u8) -> bool {
+                false
+            }
+        }
+
+        let mut game = Game::new(6, 6);
+        game.stamp(&Pattern::from_rows(&["OO", "OO"]), 2, 2);
+        game.set_custom_rule(Extinction);
+        assert!(game.custom_rule().is_some());
+
+        game.tick();
+        assert_eq!(game.population(), 0);
+
+        // Back to Conway
+        game.stamp(&Pattern::from_rows(&["OO", "OO"]), 2, 2);
+        game.set_rule(Rule::conway());
+        game.tick();
+        assert_eq!(game.population(), 4);
+    }
+
+    #[test]
+    fn it_decays_cells_with_a_generations_rule() {
+        let mut game = Game::new(6, 5);
+        // B2/S/C3, each cell decays for one generation before dying
+        game.set_rule(Rule::generations(&[2], &[], 3));
+        game.revive_cell_at_pos(2, 2).unwrap();
+        game.revive_cell_at_pos(3, 2).unwrap();
+
+        game.tick();
+
+        assert_eq!(live_positions(&game), vec![(2, 1), (3, 1), (2, 3), (3, 3)]);
+        let cells = game.cells();
+        let cell_at = |x: u16, y: u16| cells.iter().find(|(_, pos)| *pos == (x, y)).unwrap().0;
+        // Decaying starts at states - 1
+        assert_eq!(cell_at(2, 2).dying(), Some(2));
+        assert_eq!(cell_at(3, 2).dying(), Some(2));
+        assert!(!cell_at(2, 2).is_alive());
+
+        game.tick();
+
+        let cells = game.cells();
+        let cell_at = |x: u16, y: u16| cells.iter().find(|(_, pos)| *pos == (x, y)).unwrap().0;
+        // The decaying cells are dead now, and they weren't counted as
+        // neighbours nor could they be born again
+        assert_eq!(cell_at(2, 2).dying(), None);
+        assert!(!cell_at(2, 2).is_alive());
+        assert_eq!(cell_at(2, 1).dying(), Some(2));
+        assert_eq!(
+            live_positions(&game),
+            vec![(2, 0), (3, 0), (1, 2), (4, 2), (2, 4), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn it_finds_the_live_bounds() {
+        let mut game = Game::new(10, 10);
+        assert_eq!(game.live_bounds(), None);
+
+        game.revive_cell_at_pos(3, 7).unwrap();
+        game.revive_cell_at_pos(6, 2).unwrap();
+        game.revive_cell_at_pos(4, 4).unwrap();
+
+        assert_eq!(game.live_bounds(), Some((3, 2, 6, 7)));
+    }
+
+    #[test]
+    fn it_handles_zero_sized_boards() {
+        for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+            let mut game = Game::new(width, height);
+            assert_eq!(game.size(), (0, 0));
+            assert_eq!(game.iter_cells().count(), 0);
+            assert!(game.find_cell_at_pos(0, 0).is_none());
+            assert!(game.revive_cell_at_pos(0, 0).is_err());
+
+            for topology in [Topology::Bounded, Topology::Toroidal, Topology::Mirror] {
+                game.set_topology(topology);
+                game.tick();
+            }
+            game.set_auto_grow(true);
+            game.set_topology(Topology::Bounded);
+            game.tick();
+            game.randomize(0.5);
+            game.fill_checkerboard();
+            game.invert();
+            game.translate(1, 1);
+            assert!(!game.stamp_centered(&Pattern::from_rows(&["OOO"])));
+            assert_eq!(game.generation(), 4);
+            assert_eq!(game.population(), 0);
+            assert_eq!(game.live_bounds(), None);
+            assert_eq!(game.neighbour_histogram(), [0; 9]);
+            assert!(game.is_stable());
+
+            assert!(game.resize(3, 2));
+            assert_eq!(game.size(), (3, 2));
+            assert!(game.resize(0, 2));
+            assert_eq!(game.size(), (0, 0));
+            assert!(game.resize_if_larger(4, 0));
+            assert_eq!(game.size(), (0, 0));
+        }
+    }
+
+    #[test]
+    fn it_renders_live_cells_into_a_flat_buffer() {
+        let mut game = Game::new(4, 3);
+        game.stamp(&Pattern::from_rows(&[".O", "..O", "OOO"]), 1, 0);
+        let mut buf = [7; 12];
+
+        game.render_into(&mut buf);
+
+        #[rustfmt::skip]
+        assert_eq!(buf, [
+            0, 0, 1, 0,
+            0, 0, 0, 1,
+            0, 1, 1, 1,
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer length")]
+    fn it_needs_a_buffer_the_size_of_the_board() {
+        Game::new(4, 3).render_into(&mut [0; 11]);
+    }
+
+    #[test]
+    fn it_shows_cells_to_library_users() {
+        let mut game = Game::new(4, 3);
+        game.revive_cell_at_pos(2, 1).unwrap();
+
+        let cell = game.get(2, 1).unwrap();
+        assert_eq!((cell.x(), cell.y()), (2, 1));
+        assert_eq!(cell.kind(), CellKind::Alive);
+        assert_eq!(cell, &Cell::alive(2, 1));
+        assert_eq!(game.get(0, 0), Some(&Cell::dead(0, 0)));
+        assert_eq!(game.get(4, 0), None);
+
+        game.set_cell(Cell::alive(0, 2)).unwrap();
+        game.set_cell(Cell::dead(2, 1)).unwrap();
+        assert_eq!(live_positions(&game), [(0, 2)]);
+        assert!(game.set_cell(Cell::alive(4, 0)).is_err());
+    }
+
+    #[test]
+    fn it_counts_the_population() {
+        let mut game = Game::new(4, 4);
+        assert_eq!(game.population(), 0);
+
+        game.revive_cell_at_pos(1, 1).unwrap();
+        game.revive_cell_at_pos(2, 3).unwrap();
+        game.revive_cell_at_pos(2, 3).unwrap();
+
+        assert_eq!(game.population(), 2);
+    }
+
+    #[test]
+    fn it_counts_neighbours_according_to_the_topology() {
+        let mut game = Game::new(5, 5);
+        game.revive_cell_at_pos(1, 1).unwrap();
+        game.revive_cell_at_pos(1, 2).unwrap();
+        game.revive_cell_at_pos(4, 2).unwrap();
+
+        assert_eq!(game.get_neighbours_count_at_pos(0, 2), 2);
+
+        game.set_topology(Topology::Toroidal);
+        assert_eq!(game.get_neighbours_count_at_pos(0, 2), 3);
+
+        // (1, 1) and (1, 2) are seen once directly and once reflected
+        game.set_topology(Topology::Mirror);
+        assert_eq!(game.get_neighbours_count_at_pos(0, 2), 4);
+        // Corners reflect on both edges, so the diagonal (1, 1) is seen four
+        // times and (1, 0) twice
+        game.revive_cell_at_pos(1, 0).unwrap();
+        assert_eq!(game.get_neighbours_count_at_pos(0, 0), 6);
+    }
+
+    #[test]
+    fn it_makes_a_histogram_of_neighbour_counts() {
+        let mut game = Game::new(3, 3);
+        game.stamp(&Pattern::from_rows(&["...", "OOO", "..."]), 0, 0);
+
+        let histogram = game.neighbour_histogram();
+
+        assert_eq!(histogram, [0, 2, 5, 2, 0, 0, 0, 0, 0]);
+        assert_eq!(histogram.iter().sum::<usize>(), 3 * 3);
+    }
+
+    #[test]
+    fn it_only_changes_counts_at_the_edges_with_the_topology() {
+        let mut game = Game::new(6, 6);
+        game.randomize_with_seed(3, 0.5);
+        let cells = live_positions(&game);
+        let counts = |game: &Game| {
+            let (mut edge, mut interior) = (vec![], vec![]);
+            for (_, x, y) in game.iter_cells() {
+                let count = game.get_neighbours_count_at_pos(x, y);
+                if x == 0 || y == 0 || x == 5 || y == 5 {
+                    edge.push(count);
+                } else {
+                    interior.push(count);
+                }
+            }
+            (edge, interior)
+        };
+        let (bounded_edge, bounded_interior) = counts(&game);
+
+        game.set_topology(Topology::Toroidal);
+        let (toroidal_edge, toroidal_interior) = counts(&game);
+
+        assert_ne!(bounded_edge, toroidal_edge);
+        assert_eq!(bounded_interior, toroidal_interior);
+        assert_eq!(live_positions(&game), cells);
+    }
+
+    #[test]
+    fn it_predicts_the_changes_of_the_next_generation() {
+        let mut game = Game::new(5, 5);
+        game.stamp(&Pattern::from_rows(&["OOO"]), 1, 2);
+
+        let (births, deaths) = game.changes_next_gen();
+
+        assert_eq!(births, [(2, 1), (2, 3)]);
+        assert_eq!(deaths, [(1, 2), (3, 2)]);
+        game.tick();
+        assert_eq!(game.changes_next_gen(), (deaths, births));
+    }
+
+    #[test]
+    fn it_counts_isolated_pattern_occurrences() {
+        let blinker = Pattern::new(vec![(0, 0), (1, 0), (2, 0)]);
+        let mut game = Game::new(12, 8);
+        for x in 0..3 {
+            game.revive_cell_at_pos(x, 0).unwrap();
+            game.revive_cell_at_pos(x + 6, 4).unwrap();
+        }
+        // A longer line contains three cells in a row but isn't a blinker
+        for x in 0..4 {
+            game.revive_cell_at_pos(x + 1, 7).unwrap();
+        }
+
+        assert_eq!(game.count_pattern(&blinker), 2);
+        assert_eq!(game.count_pattern(&Pattern::new(vec![])), 0);
+    }
+
+    #[test]
+    fn it_stamps_patterns_and_clips_them_at_the_edges() {
+        let glider = Pattern::preset("glider").unwrap();
+        let mut game = Game::new(5, 5);
+
+        assert!(game.stamp(&glider, 1, 1));
+        assert_eq!(
+            live_positions(&game),
+            vec![(2, 1), (3, 2), (1, 3), (2, 3), (3, 3)]
+        );
+
+        let mut game = Game::new(5, 5);
+        assert!(!game.stamp(&glider, 3, 3));
+        assert_eq!(live_positions(&game), vec![(4, 3)]);
+    }
+
+    #[test]
+    fn it_fills_randomly_with_the_given_density() {
+        let mut game = Game::new(10, 10);
+
+        game.randomize(1.0);
+        assert_eq!(game.population(), 100);
+        game.randomize(0.0);
+        assert_eq!(game.population(), 0);
+        game.randomize(0.5);
+        assert!((20..80).contains(&game.population()));
+    }
+
+    #[test]
+    fn it_fills_the_same_way_with_the_same_seed() {
+        let board = |seed| {
+            let mut game = Game::new(20, 10);
+            game.randomize_with_seed(seed, 0.25);
+            live_positions(&game)
+        };
+
+        assert_eq!(board(42), board(42));
+        assert_ne!(board(42), board(43));
+
+        let mut game = Game::new(20, 10);
+        let seed = game.randomize(0.25);
+        assert_eq!(live_positions(&game), board(seed));
+    }
+
+    #[test]
+    fn it_mutates_cells_after_the_rule() {
+        let start = |rate| {
+            let mut game = Game::new(20, 10);
+            game.randomize_with_seed(7, 0.3);
+            game.set_mutation_rate(rate);
+            game
+        };
+        let mut unmutated = start(0.0);
+        unmutated.tick();
+        let after_rule = live_positions(&unmutated);
+
+        // Nothing random happens at 0, whatever the seed
+        let mut game = start(0.0);
+        game.set_mutation_seed(1);
+        game.tick();
+        assert_eq!(live_positions(&game), after_rule);
+
+        let mut game = start(1.0);
+        game.tick();
+        let mut inverted = start(0.0);
+        inverted.tick();
+        inverted.invert();
+        assert_eq!(live_positions(&game), live_positions(&inverted));
+        assert!(!game.is_stable());
+    }
+
+    #[test]
+    fn it_does_not_mutate_dead_borders() {
+        let mut game = Game::new(6, 6);
+        game.set_border_dead(true);
+        game.set_mutation_rate(1.0);
+
+        game.tick();
+
+        // Only the 4x4 inside flipped
+        assert_eq!(game.population(), 16);
+        assert!(game
+            .live_cells()
+            .all(|(x, y)| (1..5).contains(&x) && (1..5).contains(&y)));
+    }
+
+    #[test]
+    fn it_detects_periods_without_changing_the_mutations() {
+        let mutating = || {
+            let mut game = Game::new(8, 8);
+            game.set_mutation_rate(0.1);
+            game.set_mutation_seed(3);
+            game.stamp(&Pattern::preset("blinker").unwrap(), 2, 2);
+            game
+        };
+        let mut game = mutating();
+        let mut untouched = mutating();
+
+        assert_eq!(game.detect_period(10), Some(2));
+        assert_eq!(game.mutation_rate(), 0.1);
+        game.tick();
+        untouched.tick();
+
+        assert_eq!(live_positions(&game), live_positions(&untouched));
+    }
+
+    #[test]
+    fn it_fills_a_checkerboard() {
+        let mut game = Game::new(5, 3);
+
+        game.fill_checkerboard();
+
+        assert_eq!(game.population(), 8);
+        assert_eq!(live_positions(&game)[..3], [(0, 0), (2, 0), (4, 0)]);
+    }
+
+    #[test]
+    fn it_ticks_the_same_while_reusing_the_last_generation() {
+        let mut game = Game::new(24, 16);
+        game.randomize_with_seed(3, 0.4);
+        for generation in 0..30 {
+            if generation == 10 {
+                game.resize(30, 12);
+            }
+            let mut fresh = Game::new(game.width(), game.height());
+            for (x, y) in game.live_cells() {
+                fresh.revive_cell_at_pos(x, y).unwrap();
+            }
+
+            game.tick();
+            fresh.tick();
+
+            assert_eq!(live_positions(&game), live_positions(&fresh));
+        }
+    }
+
+    #[test]
+    fn it_inverts_the_board() {
+        let mut game = Game::new(6, 4);
+        game.stamp(&Pattern::preset("glider").unwrap(), 1, 0);
+        let before = live_positions(&game);
+
+        game.invert();
+        assert_eq!(game.population(), 6 * 4 - 5);
+        assert!(!game.find_cell_at_pos(2, 0).unwrap().is_alive());
+        game.invert();
+        assert_eq!(live_positions(&game), before);
+    }
+
+    #[test]
+    fn it_keeps_the_border_dead() {
+        // A blinker next to the top edge would light up a cell in the top row
+        let mut game = Game::new(5, 5);
+        game.set_border_dead(true);
+        game.revive_cell_at_pos(1, 1).unwrap();
+        game.revive_cell_at_pos(2, 1).unwrap();
+        game.revive_cell_at_pos(3, 1).unwrap();
+
+        game.tick();
+
+        assert_eq!(live_positions(&game), vec![(2, 1), (2, 2)]);
+
+        game.set_border_dead(false);
+        game.revive_cell_at_pos(1, 1).unwrap();
+        game.revive_cell_at_pos(3, 1).unwrap();
+        game.kill_cell_at_pos(2, 2).unwrap();
+        game.tick();
+
+        assert_eq!(live_positions(&game), vec![(2, 0), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn it_copies_clears_and_pastes_regions() {
+        let mut game = Game::new(8, 6);
+        let glider = Pattern::preset("glider").unwrap();
+        game.stamp(&glider, 1, 1);
+
+        // One dead column to the left of the glider
+        let copy = game.copy_region((0, 1, 3, 3));
+        assert_eq!(copy.size(), (4, 3));
+        assert_eq!(copy.cells(), &[(2, 0), (3, 1), (1, 2), (2, 2), (3, 2)]);
+
+        game.clear_region((0, 0, 2, 5));
+        assert_eq!(live_positions(&game), vec![(3, 2), (3, 3)]);
+
+        // Pasting adds to what's there, unless overwriting
+        game.revive_cell_at_pos(4, 4).unwrap();
+        game.stamp(&copy, 4, 2);
+        assert_eq!(game.population(), 8);
+        game.stamp_overwriting(&copy, 4, 2);
+        assert_eq!(
+            live_positions(&game),
+            vec![(3, 2), (6, 2), (3, 3), (7, 3), (5, 4), (6, 4), (7, 4)]
+        );
+
+        // Past the edge of the board the copy is clipped
+        assert_eq!(game.copy_region((6, 4, 20, 20)).size(), (2, 2));
+    }
+
+    #[test]
+    fn it_lists_only_live_cells() {
+        let mut game = Game::new(6, 4);
+        game.revive_cell_at_pos(5, 0).unwrap();
+        game.revive_cell_at_pos(2, 3).unwrap();
+        game.revive_cell_at_pos(0, 1).unwrap();
+        game.revive_cell_at_pos(3, 3).unwrap();
+        game.kill_cell_at_pos(3, 3).unwrap();
+
+        let live: Vec<_> = game.live_cells().collect();
+
+        assert_eq!(live, vec![(5, 0), (0, 1), (2, 3)]);
+        assert_eq!(Game::new(3, 3).live_cells().count(), 0);
+    }
+
+    #[test]
+    fn it_iterates_over_the_same_cells_as_the_vec() {
+        let mut game = Game::new(4, 3);
+        game.revive_cell_at_pos(1, 2).unwrap();
+        game.revive_cell_at_pos(3, 0).unwrap();
+
+        let iterated: Vec<_> = game
+            .iter_cells()
+            .map(|(cell, x, y)| (cell, (x, y)))
+            .collect();
+
+        assert_eq!(iterated.len(), 12);
+        assert_eq!(iterated, game.cells());
+        assert_eq!(iterated[4].1, (0, 1));
+    }
+
+    #[test]
+    fn it_toggles_cells() {
+        let mut game = Game::new(3, 3);
+
+        assert!(game.toggle_cell_at_pos(1, 2).is_ok());
+        assert_eq!(live_positions(&game), vec![(1, 2)]);
+        game.toggle_cell_at_pos(1, 2).unwrap();
+        assert_eq!(game.population(), 0);
+        let revision = game.revision();
+        let error = game.toggle_cell_at_pos(3, 0).unwrap_err();
+        assert!(matches!(error, GameError::OutOfBounds { x: 3, y: 0, .. }));
+        assert_eq!(error.to_string(), "(3, 0) is outside the 3x3 board");
+        assert_eq!(game.revision(), revision);
+    }
+
+    #[test]
+    fn it_tells_still_lifes_from_oscillators() {
+        let mut game = Game::new(6, 6);
+        assert!(game.is_stable());
+
+        game.stamp(&Pattern::preset("beacon").unwrap(), 1, 1);
+        assert!(!game.is_stable());
+
+        game.clear_region((0, 0, 5, 5));
+        game.stamp(&Pattern::from_rows(&["OO", "OO"]), 2, 2);
+        assert!(game.is_stable());
+    }
+
+    #[test]
+    fn it_reports_cell_positions() {
+        let mut game = Game::new(6, 4);
+        game.revive_cell_at_pos(4, 2).unwrap();
+
+        let cell = game.find_cell_at_pos(4, 2).unwrap();
+        assert_eq!(cell.position(), (4, 2));
+        assert!(game
+            .cells()
+            .iter()
+            .all(|(cell, pos)| cell.position() == *pos));
+    }
+
+    #[test]
+    fn it_detects_periods() {
+        let mut game = Game::new(6, 6);
+        game.stamp(&Pattern::preset("blinker").unwrap(), 1, 2);
+        assert_eq!(game.detect_period(10), Some(2));
+
+        game.clear_region((0, 0, 5, 5));
+        game.stamp(&Pattern::from_rows(&["OO", "OO"]), 2, 2);
+        assert_eq!(game.detect_period(10), Some(1));
+    }
+
+    #[test]
+    fn it_detects_a_glider_wrapping_around_a_torus() {
+        // A glider moves one cell diagonally every 4 generations, so it takes
+        // 4 * 8 to cross the board and be back where it started
+        let mut game = Game::new(8, 8);
+        game.set_topology(Topology::Toroidal);
+        game.stamp(&Pattern::preset("glider").unwrap(), 2, 2);
+        let before = live_positions(&game);
+
+        assert_eq!(game.detect_period(31), None);
+        assert_eq!(game.detect_period(100), Some(32));
+
+        assert_eq!(live_positions(&game), before);
+        assert_eq!(game.generation(), 0);
+    }
+
+    #[test]
+    fn it_ages_surviving_cells() {
+        // A block with a cell that will be born next to it
+        let mut game = Game::new(6, 6);
+        game.stamp(&Pattern::from_rows(&["OO", "OO"]), 1, 1);
+        game.tick();
+        game.tick();
+        game.revive_cell_at_pos(4, 1).unwrap();
+        let age_at = |game: &Game, x, y| game.find_cell_at_pos(x, y).unwrap().age();
+        assert_eq!(age_at(&game, 1, 1), 2);
+        assert_eq!(age_at(&game, 4, 1), 0);
+
+        game.tick();
+
+        // (3, 2) is born, (4, 1) dies, the block keeps aging
+        assert!(game.find_cell_at_pos(3, 2).unwrap().is_alive());
+        assert_eq!(age_at(&game, 3, 2), 0);
+        assert_eq!(age_at(&game, 1, 1), 3);
+        assert_eq!(age_at(&game, 4, 1), 0);
+    }
+
+    #[test]
+    fn it_counts_generations() {
+        let mut game = Game::new(3, 3);
+        assert_eq!(game.generation(), 0);
+
+        game.tick();
+        game.revive_cell_at_pos(1, 1).unwrap();
+        game.tick();
+
+        assert_eq!(game.generation(), 2);
+    }
+
+    #[test]
+    fn it_grows_both_ways_into_a_rectangle() {
+        let mut game = Game::new(2, 2);
+        game.revive_cell_at_pos(1, 1).unwrap();
+
+        game.resize_if_larger(4, 4);
+
+        assert_eq!(game.size(), (4, 4));
+        assert!(game.cells.iter().all(|row| row.len() == 4));
+        assert!(game
+            .iter_cells()
+            .all(|(cell, x, y)| cell.position() == (x, y)));
+        assert_eq!(live_positions(&game), vec![(1, 1)]);
+
+        // Growing only one way keeps the other
+        game.resize_if_larger(2, 6);
+        assert_eq!(game.size(), (4, 6));
+    }
+
+    #[test]
+    fn it_ticks_to_a_generation() {
+        let mut game = Game::new(5, 5);
+        game.stamp(&Pattern::preset("blinker").unwrap(), 1, 2);
+        let mut reported = vec![];
+
+        game.tick_to(5, |generation| reported.push(generation));
+        assert_eq!(game.generation(), 5);
+        assert_eq!(reported, vec![1, 2, 3, 4, 5]);
+        // An odd number of ticks leaves the blinker upright
+        assert_eq!(live_positions(&game), vec![(2, 1), (2, 2), (2, 3)]);
+
+        game.tick_to(3, |_| panic!("already past generation 3"));
+        assert_eq!(game.generation(), 5);
+    }
+
+    #[test]
+    fn it_keeps_or_drops_cells_when_shrinking() {
+        for preserve in [false, true] {
+            let mut game = Game::new(5, 5);
+            game.set_preserve_on_resize(preserve);
+            game.resize(10, 8);
+            game.revive_cell_at_pos(1, 1).unwrap();
+            game.revive_cell_at_pos(8, 6).unwrap();
+
+            game.resize(5, 5);
+            game.resize(10, 8);
+
+            if preserve {
+                assert_eq!(live_positions(&game), vec![(1, 1), (8, 6)]);
+            } else {
+                assert_eq!(live_positions(&game), vec![(1, 1)]);
+            }
+            assert_eq!(game.size(), (10, 8));
+        }
+    }
+
+    #[test]
+    fn it_keeps_auto_grown_cells_when_preserving() {
+        let mut game = Game::new(5, 5);
+        game.set_preserve_on_resize(true);
+        game.set_auto_grow(true);
+        game.stamp(&Pattern::preset("blinker").unwrap(), 1, 3);
+        game.tick();
+        let grown = game.size();
+        assert!(grown.0 > 5 && grown.1 > 5);
+
+        game.resize(5, 5);
+
+        assert_eq!(game.size(), grown);
+        assert_eq!(game.population(), 3);
     }
 }
@@ -0,0 +1,202 @@
+use std::io::{self, Write};
+use std::panic;
+
+use crossterm::{cursor, event, execute, style, terminal, Result};
+
+/// Pushes the terminal's title on its title stack, in terminals that have
+/// one, so the game can change it
+const SAVE_TITLE: &str = "\x1b[22;0t";
+/// Pops the title pushed with `SAVE_TITLE` back
+const RESTORE_TITLE: &str = "\x1b[23;0t";
+
+/// Puts the terminal in raw mode on the alternate screen with mouse capture,
+/// and puts it back as it was when dropped, so the shell is usable again
+/// however the game exits. Writes go through to the wrapped writer.
+pub struct TerminalGuard<W: Write> {
+    writer: W,
+    mouse_captured: bool,
+}
+
+impl<W: Write> TerminalGuard<W> {
+    pub fn enter(writer: W) -> Result<Self> {
+        // Unwinding drops the guard only after the panic message is printed,
+        // and leaving the alternate screen would then wipe it. Restoring in
+        // the hook first keeps the message on the normal screen. Installed
+        // first, so there's no moment a panic leaves the terminal raw.
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore(&mut io::stdout());
+            default_hook(info);
+        }));
+
+        terminal::enable_raw_mode()?;
+        // Created right away so a failure below still restores the terminal
+        let mut guard = Self {
+            writer,
+            mouse_captured: false,
+        };
+        setup(&mut guard.writer)?;
+        // The game can do without the mouse, with the keyboard cursor
+        guard.mouse_captured = match execute!(guard.writer, event::EnableMouseCapture) {
+            Ok(()) => true,
+            Err(error) => {
+                log::warn!("can't capture the mouse: {}", error);
+                false
+            }
+        };
+
+        Ok(guard)
+    }
+
+    /// Whether turning on mouse capture worked. Terminals that ignore it
+    /// can't be told apart, they just never send mouse events.
+    pub fn mouse_captured(&self) -> bool {
+        self.mouse_captured
+    }
+}
+
+impl<W: Write> Write for TerminalGuard<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Drop for TerminalGuard<W> {
+    fn drop(&mut self) {
+        restore(&mut self.writer);
+    }
+}
+
+/// What `TerminalGuard::enter` does after raw mode, but for the mouse
+fn setup(writer: &mut impl Write) -> Result<()> {
+    execute!(
+        writer,
+        terminal::EnterAlternateScreen,
+        style::Print(SAVE_TITLE)
+    )
+}
+
+/// Stops the process, the way Ctrl+z does outside of raw mode, with the
+/// terminal restored for the shell meanwhile. Returns once it's resumed with
+/// `fg`, with the terminal set up again but left for the caller to redraw
+/// and capture the mouse again.
+#[cfg(unix)]
+pub fn suspend(writer: &mut impl Write) -> Result<()> {
+    restore(writer);
+    // SIGTSTP stops every thread until SIGCONT, so this goes on from here
+    // once resumed
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    terminal::enable_raw_mode()?;
+    setup(writer)
+}
+
+/// Undoes everything `TerminalGuard::enter` and the game did to the
+/// terminal. Errors are ignored, there's nothing left to do about them.
+///
+/// The title is cleared before the saved one is restored, for terminals
+/// without a title stack.
+fn restore(writer: &mut impl Write) {
+    let _ = execute!(
+        writer,
+        terminal::SetTitle(""),
+        style::Print(RESTORE_TITLE),
+        style::ResetColor,
+        cursor::Show,
+        event::DisableMouseCapture,
+        terminal::LeaveAlternateScreen
+    );
+    let _ = terminal::disable_raw_mode();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer whose output can still be read after it was moved into a guard
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Writer whose first write fails, like a terminal going away mid-frame,
+    /// and whose later ones go to the buffer
+    struct FailingOnce {
+        buffer: SharedBuffer,
+        failed: bool,
+    }
+
+    impl Write for FailingOnce {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(io::Error::other("lost the terminal"));
+            }
+            self.buffer.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_restores_the_terminal_on_errors() {
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let run = move || -> io::Result<()> {
+            let mut guard = TerminalGuard {
+                writer: FailingOnce {
+                    buffer: writer,
+                    failed: false,
+                },
+                mouse_captured: true,
+            };
+            write!(guard, "board")?;
+            Ok(())
+        };
+
+        assert_eq!(run().unwrap_err().to_string(), "lost the terminal");
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("board"));
+        assert!(output.contains("\x1b[?1049l"), "left the alternate screen");
+        assert!(output.contains("\x1b[?25h"), "showed the cursor");
+    }
+
+    #[test]
+    fn it_restores_the_terminal_when_panicking() {
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+
+        let result = panic::catch_unwind(move || {
+            // Without entering, which needs a real terminal
+            let mut guard = TerminalGuard {
+                writer,
+                mouse_captured: true,
+            };
+            write!(guard, "board").unwrap();
+            panic!("bug");
+        });
+
+        assert!(result.is_err());
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.starts_with("board"));
+        assert!(output.contains("\x1b[?1049l"), "left the alternate screen");
+        assert!(output.contains("\x1b[?25h"), "showed the cursor");
+        assert!(output.contains(RESTORE_TITLE), "restored the title");
+    }
+}
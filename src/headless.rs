@@ -0,0 +1,170 @@
+use std::io::Write;
+use std::time::Instant;
+
+use gameoflife::{format, Game, Pattern, Rule};
+
+use crate::cli::Options;
+
+/// Board size to start with when there's neither a fixed size nor a pattern
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+/// Board size for `--bench` without a fixed size
+const BENCH_SIZE: (u16, u16) = (256, 256);
+/// Generations `--bench` runs without `--generations`
+const BENCH_GENERATIONS: u64 = 100;
+
+/// Runs `--generations` generations as fast as they go, or up to
+/// `--max-generations` if that comes first, with no terminal UI,
+/// then writes the live cells to `--out`, or prints them to `output` as
+/// plaintext. `pattern` is the `--pattern` file with its rule, already read.
+pub fn run(
+    options: &Options,
+    pattern: Option<(Pattern, Option<Rule>)>,
+    output: &mut impl Write,
+) -> Result<(), String> {
+    let game = simulate(options, pattern);
+    match &options.out {
+        Some(file) => crate::write_live_cells(file, &game)
+            .map_err(|error| format!("can't write {}: {}", file, error)),
+        None => output
+            .write_all(format::to_plaintext(&crate::live_pattern(&game)).as_bytes())
+            .map_err(|error| format!("can't print the board: {}", error)),
+    }
+}
+
+/// Ticks a random board, at `--density` and from `--seed` if given, for
+/// `--generations` and prints how long it took on a single line of
+/// `name=value` pairs.
+pub fn bench(options: &Options, output: &mut impl Write) -> Result<(), String> {
+    let (width, height) = options.size(BENCH_SIZE).unwrap_or(BENCH_SIZE);
+    let generations = options.generations.unwrap_or(BENCH_GENERATIONS);
+    let mut game = Game::new(width, height);
+    match options.seed {
+        Some(seed) => game.randomize_with_seed(seed, options.density),
+        None => {
+            game.randomize(options.density);
+        }
+    }
+
+    let start = Instant::now();
+    game.tick_to(generations, |_| {});
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let cells = u64::from(width) * u64::from(height);
+    // A board that ticks faster than the clock can measure still gets a rate
+    let per_second = |count: u64| count as f64 / elapsed.max(f64::MIN_POSITIVE);
+    writeln!(
+        output,
+        "size={}x{} generations={} elapsed={:.6}s generations_per_second={:.1} cells_per_second={:.0}",
+        width,
+        height,
+        generations,
+        elapsed,
+        per_second(generations),
+        per_second(generations * cells)
+    )
+    .map_err(|error| format!("can't print the results: {}", error))
+}
+
+/// Sets the board up like the TUI would and ticks it. Without a fixed size,
+/// the board grows as needed, as if it were infinite.
+fn simulate(options: &Options, pattern: Option<(Pattern, Option<Rule>)>) -> Game {
+    let start_size = pattern.as_ref().map_or(DEFAULT_SIZE, |(pattern, _)| {
+        let (width, height) = pattern.size();
+        (width.max(1), height.max(1))
+    });
+    let mut game = match options.size(start_size) {
+        Some((width, height)) => Game::new(width, height),
+        None => {
+            let (width, height) = start_size;
+            let mut game = Game::new(width, height);
+            game.set_auto_grow(true);
+            game.set_max_size(Some(crate::MAX_BOARD_SIZE));
+            game
+        }
+    };
+
+    if let Some(seed) = options.apply(&mut game) {
+        eprintln!("seed {}", seed);
+    }
+    if let Some((pattern, rule)) = pattern {
+        let file = options.pattern.as_deref().unwrap_or("the pattern piped in");
+        if let Some(warning) = crate::apply_file_rule(&mut game, options, file, rule) {
+            eprintln!("warning: {}", warning);
+        }
+        game.stamp_centered(&pattern);
+    }
+
+    let generations = match (options.generations, options.max_generations) {
+        (Some(generations), Some(max)) => generations.min(max),
+        (generations, max) => generations.or(max).unwrap_or(0),
+    };
+    game.tick_to(generations, |_| {});
+    game
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_prints_the_board_after_the_generations() {
+        let options = Options::parse(["--headless", "--generations", "1"])
+            .unwrap()
+            .unwrap();
+        let blinker = Pattern::preset("blinker").unwrap();
+        let mut output = vec![];
+
+        run(&options, Some((blinker, None)), &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "O\nO\nO\n");
+    }
+
+    #[test]
+    fn it_reports_the_benchmark_on_one_line() {
+        let options = Options::parse(["--bench", "--size", "20x10", "--generations", "3"])
+            .unwrap()
+            .unwrap();
+        let mut output = vec![];
+
+        bench(&options, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.starts_with("size=20x10 generations=3 elapsed="));
+        assert!(output.contains(" generations_per_second="));
+        assert!(output.contains(" cells_per_second="));
+    }
+
+    #[test]
+    fn it_ends_at_the_max_generations() {
+        let run_for = |args: &[&str]| {
+            let options = Options::parse(args.iter().copied()).unwrap().unwrap();
+            simulate(&options, None).generation()
+        };
+
+        assert_eq!(
+            run_for(&["--generations", "9", "--max-generations", "4"]),
+            4
+        );
+        assert_eq!(
+            run_for(&["--generations", "3", "--max-generations", "4"]),
+            3
+        );
+        assert_eq!(run_for(&["--max-generations", "4"]), 4);
+    }
+
+    #[test]
+    fn it_grows_the_board_unless_its_size_is_fixed() {
+        let glider = || Some((Pattern::preset("glider").unwrap(), None));
+        let options = Options::parse(["--headless", "--generations", "40"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(simulate(&options, glider()).population(), 5);
+
+        let options = Options::parse(["--headless", "--generations", "40", "--size", "3x3"])
+            .unwrap()
+            .unwrap();
+        // Hits the edges and ends up a block
+        assert_eq!(simulate(&options, glider()).population(), 4);
+    }
+}
@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Something the user can do with a key. Keys that only mean something in a
+/// mode (the cursor, the quit prompt, placing a pattern) aren't actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    ForceQuit,
+    Suspend,
+    Step,
+    PlayPause,
+    SpeedUp,
+    SlowDown,
+    ResetSpeed,
+    ToggleCursor,
+    ToggleAutoGrow,
+    ToggleCropOnShrink,
+    NextRule,
+    NextTopology,
+    FitView,
+    ShrinkBrush,
+    GrowBrush,
+    ToggleMinimap,
+    ToggleGenerationCounter,
+    NextSymmetry,
+    NextPaintColor,
+    NextGrid,
+    Invert,
+    ToggleAspect,
+    ToggleMouse,
+    TogglePreview,
+    CentrePattern,
+    ToggleFollow,
+    ToggleAdaptive,
+    Copy,
+    Cut,
+    Paste,
+    PasteSystem,
+    PickPattern,
+    Save,
+    Load,
+    GotoGeneration,
+    CommandPalette,
+    ToggleStatusBar,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 39] = [
+        Action::Quit,
+        Action::ForceQuit,
+        Action::Suspend,
+        Action::Step,
+        Action::PlayPause,
+        Action::SpeedUp,
+        Action::SlowDown,
+        Action::ResetSpeed,
+        Action::ToggleCursor,
+        Action::ToggleAutoGrow,
+        Action::ToggleCropOnShrink,
+        Action::NextRule,
+        Action::NextTopology,
+        Action::FitView,
+        Action::ShrinkBrush,
+        Action::GrowBrush,
+        Action::ToggleMinimap,
+        Action::ToggleGenerationCounter,
+        Action::NextSymmetry,
+        Action::NextPaintColor,
+        Action::NextGrid,
+        Action::Invert,
+        Action::ToggleAspect,
+        Action::ToggleMouse,
+        Action::TogglePreview,
+        Action::CentrePattern,
+        Action::ToggleFollow,
+        Action::ToggleAdaptive,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::PasteSystem,
+        Action::PickPattern,
+        Action::Save,
+        Action::Load,
+        Action::GotoGeneration,
+        Action::CommandPalette,
+        Action::ToggleStatusBar,
+        Action::Help,
+    ];
+
+    /// Name used in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::ForceQuit => "force_quit",
+            Action::Suspend => "suspend",
+            Action::Step => "step",
+            Action::PlayPause => "play_pause",
+            Action::SpeedUp => "speed_up",
+            Action::SlowDown => "slow_down",
+            Action::ResetSpeed => "reset_speed",
+            Action::ToggleCursor => "toggle_cursor",
+            Action::ToggleAutoGrow => "toggle_auto_grow",
+            Action::ToggleCropOnShrink => "toggle_crop_on_shrink",
+            Action::NextRule => "next_rule",
+            Action::NextTopology => "next_topology",
+            Action::FitView => "fit_view",
+            Action::ShrinkBrush => "shrink_brush",
+            Action::GrowBrush => "grow_brush",
+            Action::ToggleMinimap => "toggle_minimap",
+            Action::ToggleGenerationCounter => "toggle_generation_counter",
+            Action::NextSymmetry => "next_symmetry",
+            Action::NextPaintColor => "next_paint_color",
+            Action::NextGrid => "next_grid",
+            Action::Invert => "invert",
+            Action::ToggleAspect => "toggle_aspect",
+            Action::ToggleMouse => "toggle_mouse",
+            Action::TogglePreview => "toggle_preview",
+            Action::CentrePattern => "centre_pattern",
+            Action::ToggleFollow => "toggle_follow",
+            Action::ToggleAdaptive => "toggle_adaptive",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::PasteSystem => "paste_system",
+            Action::PickPattern => "pick_pattern",
+            Action::Save => "save",
+            Action::Load => "load",
+            Action::GotoGeneration => "goto_generation",
+            Action::CommandPalette => "command_palette",
+            Action::ToggleStatusBar => "toggle_status_bar",
+            Action::Help => "help",
+        }
+    }
+
+    /// What the action does, for the help
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::ForceQuit => "Quit without asking",
+            Action::Suspend => "Suspend to the shell, `fg` resumes",
+            Action::Step => "Next step",
+            Action::PlayPause => "Play/pause",
+            Action::SpeedUp => "Increase speed",
+            Action::SlowDown => "Decrease speed",
+            Action::ResetSpeed => "Reset speed",
+            Action::ToggleCursor => "Toggle the keyboard cursor",
+            Action::ToggleAutoGrow => "Toggle infinite board",
+            Action::ToggleCropOnShrink => "Toggle shrinking the board with the terminal",
+            Action::NextRule => "Switch rule",
+            Action::NextTopology => "Switch edges",
+            Action::FitView => "Centre the view on the live cells",
+            Action::ShrinkBrush => "Shrink the brush",
+            Action::GrowBrush => "Grow the brush",
+            Action::ToggleMinimap => "Toggle the minimap",
+            Action::ToggleGenerationCounter => "Toggle the generation counter",
+            Action::NextSymmetry => "Switch drawing symmetry",
+            Action::NextPaintColor => "Switch the colour cells are drawn in",
+            Action::NextGrid => "Switch the grid between off, every 5 and every 10 cells",
+            Action::Invert => "Flip every cell, dead to alive and alive to dead",
+            Action::ToggleAspect => "Draw cells two characters wide, closer to square",
+            Action::ToggleMouse => "Toggle the mouse, off lets the terminal select text",
+            Action::TogglePreview => "Show the cells about to be born and to die",
+            Action::CentrePattern => "Move the live cells to the middle",
+            Action::ToggleFollow => "Follow the live cells",
+            Action::ToggleAdaptive => "Toggle adaptive speed",
+            Action::Copy => "Copy the selection",
+            Action::Cut => "Cut the selection",
+            Action::Paste => "Paste",
+            Action::PasteSystem => "Paste a pattern from the system clipboard",
+            Action::PickPattern => "Pick a pattern to stamp",
+            Action::Save => "Save the board to a file",
+            Action::Load => "Load a pattern from a file",
+            Action::GotoGeneration => "Jump ahead to a generation",
+            Action::CommandPalette => "Type a command, like `rule B36/S23`",
+            Action::ToggleStatusBar => "Toggle the status bar",
+            Action::Help => "Show this help",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Esc", "Ctrl+c"],
+            Action::ForceQuit => &["Ctrl+q"],
+            Action::Suspend => &["Ctrl+z"],
+            Action::Step => &["Right"],
+            Action::PlayPause => &["Space"],
+            Action::SpeedUp => &["Up"],
+            Action::SlowDown => &["Down"],
+            Action::ResetSpeed => &["r"],
+            Action::ToggleCursor => &["c"],
+            Action::ToggleAutoGrow => &["e"],
+            Action::ToggleCropOnShrink => &["w"],
+            Action::NextRule => &["u"],
+            Action::NextTopology => &["t"],
+            Action::FitView => &["z", "Home"],
+            Action::ShrinkBrush => &["["],
+            Action::GrowBrush => &["]"],
+            Action::ToggleMinimap => &["m"],
+            Action::ToggleGenerationCounter => &["n"],
+            Action::NextSymmetry => &["s"],
+            Action::NextPaintColor => &["C"],
+            Action::NextGrid => &["#"],
+            Action::Invert => &["i"],
+            Action::ToggleAspect => &["a"],
+            Action::ToggleMouse => &["M"],
+            Action::TogglePreview => &["v"],
+            Action::CentrePattern => &["g"],
+            Action::ToggleFollow => &["f"],
+            Action::ToggleAdaptive => &["A"],
+            Action::Copy => &["y"],
+            Action::Cut => &["d"],
+            Action::Paste => &["p"],
+            Action::PasteSystem => &["Ctrl+v"],
+            Action::PickPattern => &["o"],
+            Action::Save => &["Ctrl+s"],
+            Action::Load => &["Ctrl+o"],
+            Action::GotoGeneration => &["G"],
+            Action::CommandPalette => &[":"],
+            Action::ToggleStatusBar => &["b"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// A key with the modifiers held with it
+pub type Key = (KeyCode, KeyModifiers);
+
+/// Which action each key does
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    actions: HashMap<Key, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut actions = HashMap::new();
+        for action in Action::ALL {
+            for key in action.default_keys() {
+                // The defaults are known to parse
+                actions.insert(parse_key(key).unwrap(), action);
+            }
+        }
+        Self { actions }
+    }
+}
+
+impl Keymap {
+    /// The default bindings, with the ones in the config file (if there is
+    /// one) replacing them.
+    pub fn load() -> Result<Self, String> {
+        let path = match config_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Self::default()),
+        };
+        let config = std::fs::read_to_string(&path)
+            .map_err(|err| format!("couldn't read {}: {}", path.display(), err))?;
+        Self::from_config(&config).map_err(|err| format!("{}: {}", path.display(), err))
+    }
+
+    /// Reads lines like `quit = "x"` or `fit_view = ["z", "Home"]`. The keys
+    /// given for an action replace its default ones.
+    pub fn from_config(config: &str) -> Result<Self, String> {
+        let mut overrides: HashMap<Action, Vec<Key>> = HashMap::new();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("expected `action = \"key\"`, got {:?}", line)))?;
+            let name = name.trim();
+            let action = Action::from_name(name)
+                .ok_or_else(|| error(format!("unknown action {:?}", name)))?;
+            if overrides.contains_key(&action) {
+                return Err(error(format!("{} is bound twice", name)));
+            }
+            let keys = parse_keys(value.trim())
+                .and_then(|keys| keys.iter().map(|key| parse_key(key)).collect())
+                .map_err(error)?;
+            overrides.insert(action, keys);
+        }
+
+        let mut actions = HashMap::new();
+        for action in Action::ALL {
+            let keys = match overrides.remove(&action) {
+                Some(keys) => keys,
+                None => action
+                    .default_keys()
+                    .iter()
+                    .map(|key| parse_key(key).unwrap())
+                    .collect(),
+            };
+            for key in keys {
+                if let Some(other) = actions.insert(key, action) {
+                    return Err(format!(
+                        "{} is bound to both {} and {}",
+                        key_name(key),
+                        other.name(),
+                        action.name()
+                    ));
+                }
+            }
+        }
+        Ok(Self { actions })
+    }
+
+    pub fn action(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        self.actions.get(&normalize((code, modifiers))).copied()
+    }
+
+    /// Keys bound to `action`, sorted by name
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        let mut keys: Vec<_> = self
+            .actions
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(&key, _)| key_name(key))
+            .collect();
+        keys.sort();
+        keys
+    }
+}
+
+/// `$XDG_CONFIG_HOME/gameoflife/keys.toml`, or under `~/.config`
+fn config_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("gameoflife").join("keys.toml"))
+}
+
+/// A quoted string or an array of them
+fn parse_keys(value: &str) -> Result<Vec<String>, String> {
+    let unquote = |value: &str| {
+        let value = value.trim();
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .map(str::to_string)
+            .ok_or_else(|| format!("expected a quoted key, got {:?}", value))
+    };
+    match value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+    {
+        Some(list) => list
+            .split(',')
+            .filter(|key| !key.trim().is_empty())
+            .map(unquote)
+            .collect(),
+        None => unquote(value).map(|key| vec![key]),
+    }
+}
+
+/// Parses keys like `q`, `A`, `Space`, `F5` or `Ctrl+s`
+fn parse_key(key: &str) -> Result<Key, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+    loop {
+        let (modifier, after) = match rest.split_once('+') {
+            // A lone `+` is the key itself
+            Some((modifier, after)) if !modifier.is_empty() => (modifier, after),
+            _ => break,
+        };
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("unknown modifier {:?} in {:?}", modifier, key)),
+        };
+        rest = after;
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "esc" => KeyCode::Esc,
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key {:?}", key)),
+            },
+        },
+    };
+    Ok(normalize((code, modifiers)))
+}
+
+/// Characters already tell whether shift was held, `A` is `Shift+a`
+fn normalize((code, modifiers): Key) -> Key {
+    match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) || c.is_uppercase() => (
+            KeyCode::Char(c.to_ascii_uppercase()),
+            modifiers - KeyModifiers::SHIFT,
+        ),
+        _ => (code, modifiers),
+    }
+}
+
+fn key_name((code, modifiers): Key) -> String {
+    let mut name = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt+");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        name.push_str("Shift+");
+    }
+    match code {
+        KeyCode::Char(' ') => name.push_str("Space"),
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+        code => name.push_str(&format!("{:?}", code)),
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_binds_the_default_keys() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.action(KeyCode::Char('q'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Esc, KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Char('A'), KeyModifiers::SHIFT),
+            Some(Action::ToggleAdaptive)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Char('q'), KeyModifiers::CONTROL),
+            Some(Action::ForceQuit)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Char('z'), KeyModifiers::CONTROL),
+            Some(Action::Suspend)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('q'), KeyModifiers::ALT), None);
+        assert_eq!(keymap.keys(Action::FitView), vec!["Home", "z"]);
+    }
+
+    #[test]
+    fn it_replaces_defaults_from_the_config() {
+        let keymap = Keymap::from_config(
+            "# Space steps instead
+step = \"Space\"
+play_pause = [\"p\", \"Ctrl+p\"]
+paste = \"P\"
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            keymap.action(KeyCode::Char(' '), KeyModifiers::NONE),
+            Some(Action::Step)
+        );
+        assert_eq!(keymap.action(KeyCode::Right, KeyModifiers::NONE), None);
+        assert_eq!(
+            keymap.action(KeyCode::Char('p'), KeyModifiers::CONTROL),
+            Some(Action::PlayPause)
+        );
+        assert_eq!(
+            keymap.action(KeyCode::Char('P'), KeyModifiers::SHIFT),
+            Some(Action::Paste)
+        );
+        // Untouched actions keep their keys
+        assert_eq!(
+            keymap.action(KeyCode::Char('q'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn it_rejects_bad_configs() {
+        assert_eq!(
+            Keymap::from_config("jump = \"j\""),
+            Err("line 1: unknown action \"jump\"".to_string())
+        );
+        assert_eq!(
+            Keymap::from_config("step = \"q\""),
+            Err("q is bound to both quit and step".to_string())
+        );
+        assert_eq!(
+            Keymap::from_config("\nstep = \"j\"\nstep = \"k\""),
+            Err("line 3: step is bound twice".to_string())
+        );
+        assert!(Keymap::from_config("step = j").is_err());
+        assert!(Keymap::from_config("step = \"Hyper+j\"").is_err());
+        assert!(Keymap::from_config("step").is_err());
+    }
+
+    #[test]
+    fn it_parses_keys() {
+        assert_eq!(parse_key("+"), Ok((KeyCode::Char('+'), KeyModifiers::NONE)));
+        assert_eq!(
+            parse_key("Ctrl++"),
+            Ok((KeyCode::Char('+'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(parse_key("F5"), Ok((KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(
+            parse_key("shift+a"),
+            Ok((KeyCode::Char('A'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            parse_key("Alt+PageDown"),
+            Ok((KeyCode::PageDown, KeyModifiers::ALT))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+#![warn(missing_docs)]
+
+//! The Game of Life engine behind the `gameoflife` terminal game, with
+//! nothing terminal specific in it: boards that tick by a rule, patterns and
+//! the file formats they're read from and written to.
+//!
+//! ```
+//! use gameoflife::{Game, Pattern};
+//!
+//! let mut game = Game::new(5, 5);
+//! game.stamp(&Pattern::from_rows(&["OOO"]), 1, 2);
+//! game.tick();
+//!
+//! // The blinker turned upright
+//! assert_eq!(game.generation(), 1);
+//! assert_eq!(game.population(), 3);
+//! assert!(game.find_cell_at_pos(2, 1).unwrap().is_alive());
+//! assert!(!game.find_cell_at_pos(1, 2).unwrap().is_alive());
+//! ```
+
+/// Errors from the game
+pub mod error;
+/// Reading and writing patterns in RLE, plaintext and JSON
+pub mod format;
+/// The board and how it ticks
+pub mod game;
+/// Groups of live cells to stamp on a board, built-in ones included
+pub mod pattern;
+/// Runs saved to be played again
+pub mod recording;
+mod rng;
+/// Rules deciding which cells live on
+pub mod rule;
+
+pub use error::GameError;
+pub use format::{
+    parse, parse_json, parse_plaintext, parse_rle, to_json, to_plaintext, to_rle, Format,
+    ParseError,
+};
+pub use game::{Cell, CellKind, Color, Game, GameBuilder, Neighbourhood, Position, Topology};
+pub use pattern::Pattern;
+pub use recording::Recording;
+pub use rule::{Rule, TransitionRule};
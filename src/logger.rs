@@ -0,0 +1,94 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes log records to a file, one per line, since anything printed would
+/// end up in the middle of the board
+pub struct FileLogger {
+    file: Mutex<File>,
+    level: LevelFilter,
+    start: Instant,
+}
+
+impl FileLogger {
+    fn new(file: File, level: LevelFilter) -> Self {
+        Self {
+            file: Mutex::new(file),
+            level,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Log for FileLogger {
+    /// Only the game's own records, the runtime's are too many to read
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && metadata.target().starts_with("gameoflife")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        // Written in one go so lines from different threads don't mix
+        let line = format!(
+            "{:>9.3} {:<5} {}: {}\n",
+            self.start.elapsed().as_secs_f64(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Starts logging to `path` at `level`. Without this, nothing is logged and
+/// the log calls cost no more than checking the level, which is off.
+pub fn init(path: &str, level: LevelFilter) -> io::Result<()> {
+    let logger = FileLogger::new(File::create(path)?, level);
+    log::set_boxed_logger(Box::new(logger)).map_err(io::Error::other)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+
+    #[test]
+    fn it_writes_its_own_records_up_to_its_level() {
+        let path = std::env::temp_dir().join(format!("gameoflife-log-{}", std::process::id()));
+        let logger = FileLogger::new(File::create(&path).unwrap(), LevelFilter::Info);
+        let record = |level, target, message| {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target(target)
+                    .args(format_args!("{}", message))
+                    .build(),
+            )
+        };
+
+        record(Level::Info, "gameoflife", "loaded glider.rle");
+        record(Level::Debug, "gameoflife", "fps 12");
+        record(Level::Info, "mio::poll", "registering event source");
+        logger.flush();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text.lines().count(), 1);
+        assert!(text.ends_with("INFO  gameoflife: loaded glider.rle\n"));
+    }
+}
@@ -1,39 +1,325 @@
-mod game;
-use game::Game;
+mod cli;
+mod command;
+mod guard;
+mod headless;
+mod keymap;
+mod logger;
+mod minimap;
+mod prompt;
+mod ramp;
+use cli::Options;
+use command::Command;
+use gameoflife::{
+    format, Cell, Color, Format, Game, GameError, Pattern, Recording, Rule, Topology,
+};
+use guard::TerminalGuard;
+use keymap::{Action, Keymap};
+use minimap::Minimap;
+use prompt::{Edit, LineEditor};
+use ramp::ColorRamp;
 
-use std::io::{stdout, Write};
+use std::collections::{HashSet, VecDeque};
+use std::io::{stdin, stdout, IsTerminal, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::Poll;
 use std::time::Duration;
 
 use tokio::time::{self, Instant};
-use tokio_stream::StreamExt;
+use tokio_stream::{Stream, StreamExt};
 
 use crossterm::{
     cursor,
     event::{
-        self, Event, EventStream, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent,
-        MouseEventKind,
+        self, Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
     },
     execute, queue,
     style::{self, Stylize},
     terminal, Result,
 };
 
+/// For changes to the board, which can fail in more ways than drawing can
+type GameResult = std::result::Result<(), GameError>;
+
 const DEFAULT_FPS: f32 = 6.0;
+const MIN_FPS: f32 = 0.1;
+/// Drawn in the top-left corner, both as wide so one always covers the other
+const PLAYING_INDICATOR: &str = " ▶  ";
+const PAUSED_INDICATOR: &str = " ❚❚ ";
+const MAX_FPS: f32 = 120.0;
+/// How much each speed step changes the fps by
+const FPS_FACTOR: f32 = 1.2;
+const PAN_STEP: u16 = 4;
+/// How far `PageUp` and `PageDown` move in the pattern picker
+const PICKER_PAGE: usize = 5;
+/// How close a followed pattern can get to the edges of the screen before
+/// the view recentres on it
+const FOLLOW_MARGIN: u16 = 5;
+/// Keeps an escaped glider stream from growing the board forever
+const MAX_BOARD_SIZE: (u16, u16) = (2048, 2048);
+/// How long each message stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+/// How often the progress bar is redrawn while jumping to a generation
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// How often the terminal title is updated
+const TITLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Suggested when saving or loading for the first time
+const DEFAULT_FILE_NAME: &str = "pattern.rle";
+/// Spacings of the grid drawn over dead cells, switched through after none
+const GRID_SPACINGS: [u16; 2] = [5, 10];
+/// Colours to draw cells in, switched through after drawing uncoloured
+const PAINT_COLORS: [(&str, Color); 6] = [
+    (
+        "red",
+        Color {
+            r: 230,
+            g: 60,
+            b: 60,
+        },
+    ),
+    (
+        "green",
+        Color {
+            r: 60,
+            g: 200,
+            b: 80,
+        },
+    ),
+    (
+        "blue",
+        Color {
+            r: 70,
+            g: 110,
+            b: 240,
+        },
+    ),
+    (
+        "yellow",
+        Color {
+            r: 240,
+            g: 210,
+            b: 50,
+        },
+    ),
+    (
+        "magenta",
+        Color {
+            r: 220,
+            g: 70,
+            b: 220,
+        },
+    ),
+    (
+        "cyan",
+        Color {
+            r: 60,
+            g: 210,
+            b: 220,
+        },
+    ),
+];
+const QUIT_PROMPT: &str = "Quit without saving? (y/n)";
+/// Largest the minimap gets, it's also kept to a third of the screen
+const MINIMAP_MAX_SIZE: (u16, u16) = (32, 12);
+const MAX_BRUSH: u16 = 20;
+/// Live cells stop getting dimmer after this many ticks
+const AGE_RAMP: u32 = 32;
+/// Demo mode moves on to the next pattern after this many generations even if
+/// the board never settles
+const DEMO_MAX_GENERATIONS: u32 = 500;
+/// How far back the measured tick and frame rates look
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Share of each frame adaptive mode leaves for ticking, the rest is kept
+/// for rendering and input
+const ADAPTIVE_TICK_SHARE: f32 = 0.8;
+/// Below this terminal size the board isn't rendered at all
+const MIN_SCREEN_SIZE: (u16, u16) = (20, 10);
 
 struct TuiGame<'a, W: Write> {
     game: Game,
     writer: &'a mut W,
+    /// Position of the board cell shown at the top-left corner of the screen
+    viewport: (u16, u16),
+    /// Last seen `Game::origin`, used to keep the view still when the board grows
+    origin: (u16, u16),
+    /// Whether the view tracks the live cells as they move
+    following: bool,
+    /// Terminal size, kept up to date with resize events
+    screen: (u16, u16),
+    /// Board size given with `--size`, kept whatever the terminal size
+    world_size: Option<(u16, u16)>,
+    /// Whether the bottom row of the terminal is taken by the status bar
+    show_status_bar: bool,
+    /// Whether the terminal sends mouse events, off when it can't or the
+    /// mouse was turned off to select text
+    mouse_capture: bool,
+    /// Whether cells are drawn two characters wide, as terminal characters
+    /// are about twice as tall as they're wide
+    aspect_correct: bool,
+    /// Smallest terminal size the board is rendered at
+    min_screen: (u16, u16),
+    symmetry: Symmetry,
+    fps: f32,
+    /// Whether the fps is lowered when ticks take longer than a frame
+    adaptive: bool,
+    /// How long `Game::tick` takes, averaged over the last few ticks
+    tick_time: Option<Duration>,
+    /// Ticks actually done, which can fall behind `fps`
+    tick_rate: RateMeter,
+    /// Full renders, which can be the bottleneck over a slow connection
+    frame_rate: RateMeter,
+    playing: bool,
+    /// Set while the quit confirmation is shown
+    pending_quit: bool,
+    /// Whether the board was edited by hand since it was last saved, which
+    /// makes quitting ask for confirmation
+    dirty: bool,
+    /// Board position of the keyboard cursor, `None` outside cursor mode
+    cursor: Option<(u16, u16)>,
+    /// Whether the cursor wraps to the opposite edge instead of stopping at
+    /// the edges of the board
+    cursor_wrap: bool,
+    show_minimap: bool,
+    /// Whether the generation is shown in the top-right corner, under the
+    /// minimap if it's shown
+    show_generation: bool,
+    /// Last minimap drawn, reused until the board changes
+    minimap: Option<Minimap>,
+    /// Button and screen position of the previous mouse press or drag, to
+    /// draw continuous lines
+    last_drag: Option<(MouseButton, u16, u16)>,
+    /// Radius of the disc of cells painted by the mouse
+    brush: u16,
+    /// Region picked by shift-dragging, kept until `Esc`
+    selection: Option<Selection>,
+    /// Whether a shift-drag is currently moving the end of the selection
+    selecting: bool,
+    /// Cells last copied or cut, kept for the whole session
+    clipboard: Option<Pattern>,
+    /// Pattern following the mouse or the cursor while pasting or stamping,
+    /// until `Esc`
+    placing: Option<Placing>,
+    /// Set while showing off the built-in patterns, until a key is pressed
+    demo: Option<Demo>,
+    keymap: Keymap,
+    /// Whether the help is shown over the board, which stays still meanwhile
+    show_help: bool,
+    /// Open pattern picker, which takes the keys until it's closed
+    picker: Option<Picker>,
+    /// Patterns read from files, by file name, offered in the picker after
+    /// the built-in ones
+    loaded_patterns: Vec<(String, Pattern)>,
+    /// File name or generation being typed in at the bottom of the screen
+    prompt: Option<(PromptAction, LineEditor)>,
+    /// Last file saved or loaded, suggested the next time
+    last_file: Option<String>,
+    /// Messages waiting to be shown on the bottom row, the first one being
+    /// shown
+    toasts: VecDeque<String>,
+    /// When the first of `toasts` started being shown
+    toast_since: Option<std::time::Instant>,
+    /// When the terminal title was last updated
+    title_since: Option<std::time::Instant>,
+    /// Index in `PAINT_COLORS` of the colour new cells are drawn in, `None`
+    /// for uncoloured
+    paint_color: Option<usize>,
+    /// What the next tick changes, shown over the board while it's on
+    preview: Option<Preview>,
+    /// Seed the board was filled randomly with, shown to start with the same
+    /// board again with `--seed`
+    seed: Option<u64>,
+    /// Generation to pause at while playing, from `--max-generations`
+    max_generations: Option<u64>,
+    /// Colours of decaying cells, from nearly dead to just decaying
+    ramp: ColorRamp,
+    /// Spacing of the grid drawn over dead cells, if it's shown
+    grid_every: Option<u16>,
+    /// Where the board started ticking from, for the `record` command
+    run: Option<Run>,
+    /// Whether to draw in colour, otherwise with plain characters
+    color: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Flow {
+    Continue,
+    Quit,
 }
 
+/// Terminal size, never 0 in either direction even if the terminal says so
 fn terminal_size() -> (u16, u16) {
-    terminal::size().unwrap_or((50, 30))
+    let (width, height) = terminal::size().unwrap_or((50, 30));
+    (width.max(1), height.max(1))
 }
 
 impl<'a, W: Write> TuiGame<'a, W> {
-    fn new(writer: &'a mut W) -> Self {
+    fn new(writer: &'a mut W, options: &Options, keymap: Keymap) -> Self {
         let (width, height) = terminal_size();
-        let game = Game::new(width, height);
-        Self { game, writer }
+        // Leaving room for the status bar
+        let view = (width, height.saturating_sub(1));
+        let world_size = options.size(view);
+        let (world_width, world_height) = world_size.unwrap_or(view);
+        let mut game = Game::new(world_width, world_height);
+        game.set_max_size(Some(world_size.unwrap_or(MAX_BOARD_SIZE)));
+        // Shrinking the terminal only clips the view, unless toggled
+        game.set_preserve_on_resize(true);
+        let seed = options.apply(&mut game);
+        let mut tui = Self {
+            game,
+            writer,
+            viewport: (0, 0),
+            origin: (0, 0),
+            following: false,
+            screen: (width, height),
+            world_size,
+            show_status_bar: true,
+            aspect_correct: false,
+            mouse_capture: true,
+            min_screen: MIN_SCREEN_SIZE,
+            symmetry: Symmetry::None,
+            fps: options.fps,
+            adaptive: false,
+            tick_time: None,
+            tick_rate: RateMeter::default(),
+            frame_rate: RateMeter::default(),
+            playing: options.play,
+            pending_quit: false,
+            dirty: false,
+            cursor: None,
+            cursor_wrap: false,
+            show_minimap: false,
+            show_generation: false,
+            minimap: None,
+            last_drag: None,
+            brush: 0,
+            selection: None,
+            selecting: false,
+            clipboard: None,
+            placing: None,
+            demo: None,
+            keymap,
+            show_help: false,
+            picker: None,
+            loaded_patterns: vec![],
+            prompt: None,
+            last_file: None,
+            toasts: VecDeque::new(),
+            toast_since: None,
+            title_since: None,
+            paint_color: None,
+            preview: None,
+            seed,
+            max_generations: options.max_generations,
+            ramp: ColorRamp::default(),
+            grid_every: None,
+            run: None,
+            color: true,
+        };
+        if options.demo {
+            tui.load_demo_pattern(0);
+        }
+        tui
     }
 
     async fn run(&mut self) -> Result<()> {
@@ -49,143 +335,3623 @@ impl<'a, W: Write> TuiGame<'a, W> {
     }
 
     async fn run_loop(&mut self) -> Result<()> {
-        let mut fps = DEFAULT_FPS;
-        let mut playing = false;
         let mut reader = EventStream::new();
-        let mut interval = self.interval(fps);
+        let mut frame_time = self.frame_time();
+        let mut interval = frame_interval(frame_time);
+        // Ctrl+c is a key in raw mode, but SIGINT can still be sent by other
+        // programs. Quitting lets the terminal be restored on the way out.
+        let interrupted = tokio::signal::ctrl_c();
+        tokio::pin!(interrupted);
 
         loop {
             tokio::select! {
+                Ok(()) = &mut interrupted => break,
                 _ = interval.tick() => {
-                    if playing {
+                    let now = std::time::Instant::now();
+                    self.expire_toasts(now)?;
+                    self.update_title(now)?;
+                    if self.too_small() || self.show_help || self.picker.is_some() {
+                        // Nothing to see
+                    } else if self.demo.is_some() {
+                        self.demo_tick()?;
+                    } else if self.playing {
                         self.tick()?;
                     }
                 }
                 maybe_event = reader.next() => {
-                    match maybe_event {
-                        Some(Ok(event)) => match event {
-                            Event::Mouse(MouseEvent { kind: MouseEventKind::Down(button) | MouseEventKind::Drag(button), column, row, modifiers: _ }) => match button {
-                                MouseButton::Left=>{
-                                    self.revive_cell_at_pos(column,row);
-                                },
-                                MouseButton::Right=> {
-                                    self.kill_cell_at_pos(column,row);
-                                },
-                                MouseButton::Middle => ()
-                            },
-                            Event::Key(KeyEvent { code, modifiers: _, kind: KeyEventKind::Press, state: _ }) => match code {
-                                KeyCode::Esc | KeyCode::Char('q') => break,
-                                KeyCode::Right => {
-                                    self.tick()?;
-                                },
-                                KeyCode::Up => {
-                                    fps *= 1.2;
-                                    interval = self.interval(fps);
-                                },
-                                KeyCode::Char('r') => {
-                                    fps = DEFAULT_FPS;
-                                    interval = self.interval(fps);
-                                }
-                                KeyCode::Down => {
-                                    fps /= 1.2;
-                                    interval = self.interval(fps);
-                                }
-                                KeyCode::Char(' ') => {
-                                    playing = !playing;
-                                },
-                                _ => ()
-                            },
-                            _ => ()
-                        },
-                        Some(Err(err)) => return Err(err),
-                        None => ()
+                    // Everything that's already waiting is handled before
+                    // flushing, so a fast drag is one write instead of dozens
+                    let mut flow = Flow::Continue;
+                    let mut next = maybe_event;
+                    while let Some(event) = next {
+                        flow = self.handle_event(event?)?;
+                        if flow == Flow::Quit {
+                            break;
+                        }
+                        next = ready_event(&mut reader).await;
                     }
+                    if flow == Flow::Quit {
+                        break;
+                    }
+                    self.writer.flush()?;
                 }
             }
+            if self.frame_time() != frame_time {
+                frame_time = self.frame_time();
+                interval = frame_interval(frame_time);
+            }
         }
         Ok(())
     }
 
-    fn interval(&self, with_fps: f32) -> time::Interval {
-        let duration = if with_fps > 1.0 {
-            Duration::from_secs(1) / with_fps.round() as u32
-        } else {
-            Duration::from_secs(1) * (1.0 / with_fps).round() as u32
+    fn handle_event(&mut self, event: Event) -> Result<Flow> {
+        log::trace!("{:?}", event);
+        let flow = self.dispatch_event(event)?;
+        // Editing a cell changes what its neighbours are about to do too
+        let stale = self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| preview.revision != self.game.revision());
+        if stale {
+            self.render()?;
+        }
+        Ok(flow)
+    }
+
+    fn dispatch_event(&mut self, event: Event) -> Result<Flow> {
+        match event {
+            // Terminals send a few of these in a row for one resize, only
+            // the ones changing the size need a repaint
+            Event::Resize(width, height) if (width, height) != self.screen => {
+                self.resize(width, height)?;
+            }
+            Event::Resize(..) => {}
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) if self.demo.is_some() => {
+                // Any key takes over from the demo, leaving the board as is
+                self.demo = None;
+                self.playing = false;
+            }
+            Event::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) if self.show_help => {
+                self.show_help = false;
+                self.render()?;
+            }
+            Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) if self.picker.is_some() => self.handle_picker_key(code)?,
+            Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) if self.prompt.is_some() => self.handle_prompt_key(code)?,
+            Event::Mouse(_)
+                if self.too_small()
+                    || self.demo.is_some()
+                    || self.show_help
+                    || self.picker.is_some()
+                    || self.prompt.is_some() => {}
+            Event::Mouse(event) => self.handle_mouse(event)?,
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                state: _,
+            }) => return self.handle_key(code, modifiers),
+            _ => (),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn handle_mouse(&mut self, event: MouseEvent) -> Result<()> {
+        let MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers,
+        } = event;
+        match kind {
+            MouseEventKind::Down(MouseButton::Left) if modifiers.contains(KeyModifiers::SHIFT) => {
+                let pos = self.to_board_pos_clamped(column, row);
+                self.selection = Some(Selection {
+                    start: pos,
+                    end: pos,
+                });
+                self.selecting = true;
+                self.last_drag = None;
+                return self.render();
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.selecting => {
+                let pos = self.to_board_pos_clamped(column, row);
+                if let Some(selection) = &mut self.selection {
+                    selection.end = pos;
+                }
+                return self.render();
+            }
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left)
+                if self.in_minimap(column, row) =>
+            {
+                self.last_drag = None;
+                return self.jump_to_minimap_pos(column, row);
+            }
+            MouseEventKind::Moved if self.placing.is_some() => {
+                self.move_placing(column, row);
+                return self.render();
+            }
+            MouseEventKind::Down(MouseButton::Left) if self.placing.is_some() => {
+                self.move_placing(column, row);
+                return self.place(modifiers.contains(KeyModifiers::ALT));
+            }
+            MouseEventKind::Down(button) => {
+                self.paint_with(button, column, row)?;
+                self.last_drag = Some((button, column, row));
+            }
+            MouseEventKind::Drag(button) => {
+                // Drag events skip cells when the mouse moves fast, so fill in
+                // the gap since the previous one. That one was painted already,
+                // which matters when toggling.
+                // Cells two characters wide span several positions on the
+                // line, each cell is painted once.
+                let (from, mut last) = match self.last_drag {
+                    Some((last_button, last_column, last_row)) if last_button == button => (
+                        (last_column, last_row),
+                        Some(self.to_board_pos(last_column, last_row)),
+                    ),
+                    _ => ((column, row), None),
+                };
+                for (column, row) in line(from, (column, row)) {
+                    let pos = self.to_board_pos(column, row);
+                    if last == Some(pos) {
+                        continue;
+                    }
+                    last = Some(pos);
+                    self.paint_with(button, column, row)?;
+                }
+                self.last_drag = Some((button, column, row));
+            }
+            MouseEventKind::Up(_) => {
+                self.last_drag = None;
+                self.selecting = false;
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = kind == MouseEventKind::ScrollUp;
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    return self.change_brush(if up { 1 } else { -1 });
+                }
+                self.change_fps(if up { FPS_FACTOR } else { 1.0 / FPS_FACTOR });
+            }
+            _ => (),
+        }
+        // The population may have changed. Flushed along with the other
+        // events handled at the same time.
+        self.render_play_indicator()?;
+        self.render_bottom_row()
+    }
+
+    fn paint_with(&mut self, button: MouseButton, column: u16, row: u16) -> Result<()> {
+        let painted = match button {
+            MouseButton::Left => self.revive_cell_at_pos(column, row),
+            MouseButton::Right => self.kill_cell_at_pos(column, row),
+            MouseButton::Middle => self.toggle_cell_at_pos(column, row),
+        };
+        match painted {
+            Ok(()) => Ok(()),
+            Err(error) => self.report(error),
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<Flow> {
+        let action = self.keymap.action(code, modifiers);
+        if action == Some(Action::Suspend) {
+            self.suspend()?;
+            return Ok(Flow::Continue);
+        }
+        let is_quit = matches!(action, Some(Action::Quit | Action::ForceQuit));
+        if self.too_small() && !self.pending_quit && !is_quit {
+            return Ok(Flow::Continue);
+        }
+
+        if action == Some(Action::ForceQuit) {
+            return Ok(Flow::Quit);
+        }
+
+        if self.pending_quit {
+            // Pressing the quit key twice quits too, unless it's Esc, which
+            // backs out like `n`
+            if code == KeyCode::Char('y') || (is_quit && code != KeyCode::Esc) {
+                return Ok(Flow::Quit);
+            }
+            self.pending_quit = false;
+            self.render()?;
+            return Ok(Flow::Continue);
+        }
+
+        if code == KeyCode::Esc && self.placing.is_some() {
+            self.placing = None;
+            self.render()?;
+            return Ok(Flow::Continue);
+        }
+
+        if let Some(placing) = &mut self.placing {
+            let transformed = match code {
+                KeyCode::Char('r') => Some(placing.pattern.rotate_cw()),
+                KeyCode::Char('f') => Some(placing.pattern.flip_h()),
+                KeyCode::Char('F') => Some(placing.pattern.flip_v()),
+                _ => None,
+            };
+            if let Some(pattern) = transformed {
+                placing.pattern = pattern;
+                self.render()?;
+                return Ok(Flow::Continue);
+            }
+        }
+        if code == KeyCode::Enter && self.placing.is_some() {
+            self.place(modifiers.contains(KeyModifiers::ALT))?;
+            return Ok(Flow::Continue);
+        }
+
+        if code == KeyCode::Esc && self.selection.is_some() {
+            self.selection = None;
+            self.selecting = false;
+            self.render()?;
+            return Ok(Flow::Continue);
+        }
+
+        if self.cursor.is_some() {
+            if let Some(delta) = cursor_delta(code) {
+                self.move_cursor(delta)?;
+                // Holding shift draws while moving
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    self.toggle_cell_under_cursor()?;
+                }
+                return Ok(Flow::Continue);
+            }
+            match code {
+                KeyCode::Esc => {
+                    self.cursor = None;
+                    self.render()?;
+                    return Ok(Flow::Continue);
+                }
+                KeyCode::Char('x') if self.placing.is_some() => {
+                    self.place(modifiers.contains(KeyModifiers::ALT))?;
+                    return Ok(Flow::Continue);
+                }
+                KeyCode::Enter | KeyCode::Char('x') => {
+                    self.toggle_cell_under_cursor()?;
+                    return Ok(Flow::Continue);
+                }
+                _ => (),
+            }
+        }
+
+        if modifiers == KeyModifiers::SHIFT && is_arrow(code) {
+            self.pan(code)?;
+            return Ok(Flow::Continue);
+        }
+
+        let action = match action {
+            Some(action) => action,
+            None => {
+                if let KeyCode::Char(digit @ '1'..='9') = code {
+                    let index = digit as usize - '1' as usize;
+                    if let Some(name) = Pattern::preset_names().nth(index) {
+                        // Every preset name has a pattern
+                        self.start_placing(name, Pattern::preset(name).unwrap())?;
+                    }
+                }
+                return Ok(Flow::Continue);
+            }
+        };
+        match action {
+            Action::Quit => {
+                if !self.dirty {
+                    return Ok(Flow::Quit);
+                }
+                self.pending_quit = true;
+                self.show_message(QUIT_PROMPT)?;
+                self.writer.flush()?;
+            }
+            // Handled before anything else
+            Action::ForceQuit | Action::Suspend => (),
+            Action::Step => {
+                self.tick()?;
+            }
+            Action::SpeedUp => {
+                self.change_fps(FPS_FACTOR);
+                self.refresh_status_bar()?;
+            }
+            Action::ResetSpeed => {
+                self.fps = DEFAULT_FPS;
+                self.refresh_status_bar()?;
+            }
+            Action::SlowDown => {
+                self.change_fps(1.0 / FPS_FACTOR);
+                self.refresh_status_bar()?;
+            }
+            Action::PlayPause => {
+                self.playing = !self.playing;
+                self.refresh_status_bar()?;
+            }
+            Action::ToggleCursor => {
+                self.toggle_cursor()?;
+            }
+            Action::ToggleAutoGrow => {
+                self.game.set_auto_grow(!self.game.auto_grow());
+            }
+            Action::ToggleCropOnShrink => {
+                let preserve = self.game.preserve_on_resize();
+                self.game.set_preserve_on_resize(!preserve);
+            }
+            Action::NextRule => {
+                self.next_rule();
+                self.refresh_status_bar()?;
+            }
+            Action::NextTopology => {
+                let topology = self.game.topology().next();
+                self.game.set_topology(topology);
+                self.cursor_wrap = topology == Topology::Toroidal;
+                self.refresh_status_bar()?;
+            }
+            Action::FitView => {
+                self.fit_view()?;
+            }
+            Action::ShrinkBrush => {
+                self.change_brush(-1)?;
+            }
+            Action::GrowBrush => {
+                self.change_brush(1)?;
+            }
+            Action::ToggleMinimap => {
+                self.show_minimap = !self.show_minimap;
+                self.render()?;
+            }
+            Action::ToggleGenerationCounter => {
+                self.show_generation = !self.show_generation;
+                self.render()?;
+            }
+            Action::NextSymmetry => {
+                self.symmetry = self.symmetry.next();
+            }
+            Action::NextGrid => {
+                self.grid_every = match self.grid_every {
+                    None => Some(GRID_SPACINGS[0]),
+                    Some(every) => GRID_SPACINGS
+                        .iter()
+                        .copied()
+                        .skip_while(|&spacing| spacing != every)
+                        .nth(1),
+                };
+                self.render()?;
+            }
+            Action::Invert => {
+                self.dirty = true;
+                self.game.invert();
+                self.render()?;
+            }
+            Action::NextPaintColor => {
+                self.paint_color = match self.paint_color {
+                    Some(index) if index + 1 < PAINT_COLORS.len() => Some(index + 1),
+                    Some(_) => None,
+                    None => Some(0),
+                };
+                self.refresh_status_bar()?;
+            }
+            Action::CentrePattern => {
+                self.centre_pattern()?;
+            }
+            Action::ToggleFollow => {
+                self.following = !self.following;
+                if self.following {
+                    self.fit_view()?;
+                }
+            }
+            Action::ToggleAdaptive => {
+                self.adaptive = !self.adaptive;
+                self.render()?;
+            }
+            Action::Copy => {
+                self.copy_selection(false)?;
+            }
+            Action::Cut => {
+                self.copy_selection(true)?;
+            }
+            Action::Help => {
+                self.show_help = true;
+                self.render()?;
+            }
+            Action::ToggleStatusBar => {
+                self.toggle_status_bar()?;
+            }
+            Action::ToggleAspect => {
+                self.toggle_aspect()?;
+            }
+            Action::ToggleMouse => {
+                self.toggle_mouse()?;
+            }
+            Action::TogglePreview => {
+                self.preview = match self.preview {
+                    Some(_) => None,
+                    None => Some(Preview::new(&self.game)),
+                };
+                self.render()?;
+            }
+            Action::Paste => match self.clipboard.clone() {
+                Some(pattern) => self.start_placing("clipboard", pattern)?,
+                None => {
+                    let message = format!(
+                        "Nothing copied, select and press {} or {} first",
+                        self.keymap.keys(Action::Copy).join("/"),
+                        self.keymap.keys(Action::Cut).join("/"),
+                    );
+                    self.toast(message)?;
+                }
+            },
+            Action::PasteSystem => {
+                self.paste_system()?;
+            }
+            Action::PickPattern => {
+                self.open_picker()?;
+            }
+            Action::Save => {
+                self.open_prompt(PromptAction::Save)?;
+            }
+            Action::Load => {
+                self.open_prompt(PromptAction::Load)?;
+            }
+            Action::GotoGeneration => {
+                self.open_prompt(PromptAction::GotoGeneration)?;
+            }
+            Action::CommandPalette => {
+                self.open_prompt(PromptAction::Command)?;
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Multiplies the fps by `factor`, within `MIN_FPS` and `MAX_FPS`. The run
+    /// loop picks up the new speed after the event.
+    fn change_fps(&mut self, factor: f32) {
+        self.fps = (self.fps * factor).clamp(MIN_FPS, MAX_FPS);
+        log::debug!("fps set to {}", self.fps);
+    }
+
+    fn change_brush(&mut self, delta: i32) -> Result<()> {
+        self.brush = (i32::from(self.brush) + delta).clamp(0, i32::from(MAX_BRUSH)) as u16;
+        self.toast(format!("Brush radius: {}", self.brush))
+    }
+
+    /// Time between ticks, at a lower fps than asked for if ticks are too slow
+    /// in adaptive mode
+    fn frame_time(&self) -> Duration {
+        let fps = match self.tick_time {
+            Some(tick_time) if self.adaptive => adaptive_fps(self.fps, tick_time),
+            _ => self.fps,
         };
-        time::interval_at(Instant::now() + duration, duration)
+        tick_interval(fps)
     }
 
     fn tick(&mut self) -> Result<()> {
+        self.note_run_start();
+        let start = std::time::Instant::now();
         self.game.tick();
+        self.end_run_tick();
+        let elapsed = start.elapsed();
+        self.tick_rate.record(start);
+        log::trace!(
+            "generation {} ticked in {:?}",
+            self.game.generation(),
+            elapsed
+        );
+        // Averaged so a single slow tick doesn't slow everything down
+        self.tick_time = Some(match self.tick_time {
+            Some(average) => (average * 3 + elapsed) / 4,
+            None => elapsed,
+        });
+
+        self.follow_origin();
+        if self.following {
+            self.follow_pattern();
+        }
+        // Nothing left to watch once the board settles. An empty board keeps
+        // going, so what's drawn on it comes to life right away.
+        let settled = self.game.population() > 0 && self.game.is_stable();
+        if self.playing && self.demo.is_none() && settled {
+            self.playing = false;
+        }
+        // Checked after every single tick, so it stops right on the limit.
+        // Stepping or playing on past it is still possible.
+        let generation = self.game.generation();
+        if self.playing && self.max_generations == Some(generation) {
+            self.playing = false;
+            self.toast(format!("Stopped at generation {}", generation))?;
+        }
+        self.render()
+    }
+
+    /// Remembers the board before it starts ticking, or when it was changed
+    /// since the last tick, as the start of the run to `record`
+    fn note_run_start(&mut self) {
+        let revision = self.game.revision();
+        if self.run.as_ref().map(|run| run.revision) != Some(revision) {
+            self.run = Some(Run {
+                recording: Recording::new(&self.game),
+                generation: self.game.generation(),
+                revision,
+            });
+        }
+    }
+
+    /// Keeps the run going after ticking, which changed the revision
+    fn end_run_tick(&mut self) {
+        if let Some(run) = &mut self.run {
+            run.revision = self.game.revision();
+        }
+    }
+
+    /// Plays `recording` from its start on a board of its size, pausing
+    /// where it ended
+    fn start_replay(&mut self, recording: &Recording) {
+        let mut game = recording.game();
+        game.set_max_size(Some(game.size()));
+        game.set_preserve_on_resize(true);
+        self.world_size = Some(game.size());
+        self.origin = game.origin();
+        self.game = game;
+        self.max_generations = Some(recording.generations);
+        self.playing = true;
+    }
+
+    /// Ticks, or once the board settles or has run for long enough, moves on
+    /// to the next pattern.
+    fn demo_tick(&mut self) -> Result<()> {
+        let demo = match &mut self.demo {
+            Some(demo) => demo,
+            None => return Ok(()),
+        };
+        demo.generations += 1;
+        if self.game.is_stable() || demo.generations > DEMO_MAX_GENERATIONS {
+            let next = demo.pattern + 1;
+            self.load_demo_pattern(next);
+            return self.render();
+        }
+        self.tick()
+    }
+
+    /// Clears the board and puts the `index`th built-in pattern, wrapping
+    /// around, in the middle of it.
+    fn load_demo_pattern(&mut self, index: usize) {
+        let index = index % Pattern::preset_names().count();
+        // Every preset name has a pattern
+        let name = Pattern::preset_names().nth(index).unwrap();
+        let (width, height) = self.game.size();
+        self.game
+            .clear_region((0, 0, width.saturating_sub(1), height.saturating_sub(1)));
+        self.game.stamp_centered(&Pattern::preset(name).unwrap());
+        self.demo = Some(Demo {
+            pattern: index,
+            generations: 0,
+        });
+    }
+
+    /// Shifts the viewport by however much the board grew to the left or top,
+    /// so the cells on screen stay where they were.
+    fn follow_origin(&mut self) {
+        let (x, y) = self.game.origin();
+        self.viewport.0 += x - self.origin.0;
+        self.viewport.1 += y - self.origin.1;
+        if let Some(selection) = &mut self.selection {
+            selection.translate(x - self.origin.0, y - self.origin.1);
+        }
+        self.origin = (x, y);
+    }
+
+    fn next_rule(&mut self) {
+        let presets = Rule::presets();
+        let current = presets.iter().position(|rule| rule == self.game.rule());
+        let next = current.map_or(0, |i| (i + 1) % presets.len());
+        self.game.set_rule(presets[next].clone());
+    }
+
+    /// Recentres the view on the live cells once they get too close to the
+    /// edges of the screen.
+    fn follow_pattern(&mut self) {
+        let bounds = match self.game.live_bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let (min_x, min_y, max_x, max_y) = bounds;
+        let (width, height) = self.board_view_size();
+        let (left, top) = self.viewport;
+        let near_edge = min_x < left + FOLLOW_MARGIN
+            || min_y < top + FOLLOW_MARGIN
+            || u32::from(max_x) + u32::from(FOLLOW_MARGIN) >= u32::from(left) + u32::from(width)
+            || u32::from(max_y) + u32::from(FOLLOW_MARGIN) >= u32::from(top) + u32::from(height);
+        if near_edge {
+            self.centre_on(bounds);
+        }
+    }
+
+    fn pan(&mut self, direction: KeyCode) -> Result<()> {
+        self.following = false;
+        let (x, y) = self.viewport;
+        self.viewport = match direction {
+            KeyCode::Left => (x.saturating_sub(PAN_STEP), y),
+            KeyCode::Right => (x.saturating_add(PAN_STEP), y),
+            KeyCode::Up => (x, y.saturating_sub(PAN_STEP)),
+            KeyCode::Down => (x, y.saturating_add(PAN_STEP)),
+            _ => (x, y),
+        };
+        self.render()
+    }
+
+    /// Centres the view on the live cells, or on the middle of the board if
+    /// there are none.
+    fn fit_view(&mut self) -> Result<()> {
+        let (board_width, board_height) = self.game.size();
+        let bounds = self.game.live_bounds().unwrap_or((
+            0,
+            0,
+            board_width.saturating_sub(1),
+            board_height.saturating_sub(1),
+        ));
+        self.centre_on(bounds);
+        self.render()
+    }
+
+    /// Moves the live cells to the middle of the screen.
+    fn centre_pattern(&mut self) -> Result<()> {
+        let (min_x, min_y, max_x, max_y) = match self.game.live_bounds() {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        let (width, height) = self.board_view_size();
+        let dx =
+            i32::from(self.viewport.0) + i32::from(width / 2) - i32::from(midpoint(min_x, max_x));
+        let dy =
+            i32::from(self.viewport.1) + i32::from(height / 2) - i32::from(midpoint(min_y, max_y));
+        self.game.translate(dx, dy);
+        self.render()
+    }
+
+    fn centre_on(&mut self, (min_x, min_y, max_x, max_y): (u16, u16, u16, u16)) {
+        let (width, height) = self.board_view_size();
+        self.viewport = (
+            midpoint(min_x, max_x).saturating_sub(width / 2),
+            midpoint(min_y, max_y).saturating_sub(height / 2),
+        );
+    }
+
+    /// Follows the new terminal size, resizing the board with it unless the
+    /// board has a fixed `world_size`.
+    fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        log::debug!("terminal resized to {}x{}", width, height);
+        self.screen = (width.max(1), height.max(1));
+        let (width, height) = self.board_view_size();
+        if self.world_size.is_none() {
+            // Too small a terminal is most likely a mistake, no cells are
+            // dropped
+            if self.too_small() {
+                self.game.resize_if_larger(width, height);
+            } else {
+                self.game.resize(width, height);
+            }
+        }
+        execute!(self.writer, terminal::Clear(terminal::ClearType::All))?;
         self.render()
     }
 
+    /// Part of the screen showing the board, above the status bar
+    fn view_size(&self) -> (u16, u16) {
+        let (width, height) = self.screen;
+        if self.show_status_bar {
+            (width, height.saturating_sub(1))
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Board cells fitting in `view_size`, fewer across when they're drawn
+    /// two characters wide
+    fn board_view_size(&self) -> (u16, u16) {
+        let (width, height) = self.view_size();
+        (width / self.cell_width(), height)
+    }
+
+    /// Characters a cell takes on screen
+    fn cell_width(&self) -> u16 {
+        if self.aspect_correct {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Switches between drawing cells one and two characters wide. The board
+    /// doesn't shrink, the same as when the terminal gets narrower.
+    fn toggle_aspect(&mut self) -> Result<()> {
+        self.aspect_correct = !self.aspect_correct;
+        if self.world_size.is_none() {
+            let (width, height) = self.board_view_size();
+            self.game.resize_if_larger(width, height);
+        }
+        execute!(self.writer, terminal::Clear(terminal::ClearType::All))?;
+        self.render()
+    }
+
+    fn toggle_status_bar(&mut self) -> Result<()> {
+        self.show_status_bar = !self.show_status_bar;
+        if self.world_size.is_none() {
+            let (width, height) = self.board_view_size();
+            self.game.resize_if_larger(width, height);
+        }
+        self.render()
+    }
+
+    fn too_small(&self) -> bool {
+        self.screen.0 < self.min_screen.0 || self.screen.1 < self.min_screen.1
+    }
+
+    fn render_too_small(&mut self) -> Result<()> {
+        let (width, height) = self.screen;
+        let message = format!(
+            "Terminal too small ({}x{}), need at least {}x{}",
+            width, height, self.min_screen.0, self.min_screen.1
+        );
+        let column = width.saturating_sub(message.len() as u16) / 2;
+        execute!(
+            self.writer,
+            style::ResetColor,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(column, height / 2),
+            style::Print(message)
+        )
+    }
+
     fn render(&mut self) -> Result<()> {
-        let (width, height) = terminal_size();
-        self.game.resize_if_larger(width, height);
+        if self.too_small() {
+            return self.render_too_small();
+        }
+
+        let (width, height) = self.board_view_size();
+        self.clamp_viewport(width, height);
+        if self.preview.is_some() {
+            self.preview = Some(Preview::new(&self.game));
+        }
         execute!(self.writer, cursor::MoveTo(0, 0))?;
 
-        for (cell, (x, y)) in self.game.cells() {
-            let content = match cell.is_alive() {
-                true => " ".on_white(),
-                false => " ".on_black(),
-            };
-            queue!(
-                self.writer,
-                cursor::MoveTo(x, y),
-                style::PrintStyledContent(content)
-            )?;
+        let (left, top) = self.viewport;
+        let cell_width = self.cell_width();
+        for (cell, x, y) in self.game.iter_cells() {
+            if x < left || y < top || x - left >= width || y - top >= height {
+                continue;
+            }
+            let content = self.styled_cell(cell, x, y);
+            let column = (x - left) * cell_width;
+            print_cell(&mut self.writer, (column, y - top), content, cell_width)?;
+        }
+        self.render_cursor()?;
+        self.render_minimap()?;
+        self.render_generation_counter()?;
+        self.render_play_indicator()?;
+        self.render_position_indicator()?;
+        self.render_status_label()?;
+        self.render_bottom_row()?;
+        if self.picker.is_some() {
+            self.render_picker()?;
+        }
+        self.render_prompt()?;
+        if self.show_help {
+            self.render_help()?;
         }
         self.writer.flush()?;
+        self.frame_rate.record(std::time::Instant::now());
 
         Ok(())
     }
 
-    fn revive_cell_at_pos(&mut self, x: u16, y: u16) -> Option<()> {
-        self.game.revive_cell_at_pos(x, y)?;
+    fn open_prompt(&mut self, action: PromptAction) -> Result<()> {
+        let input = match action {
+            PromptAction::Save | PromptAction::Load => {
+                self.last_file.as_deref().unwrap_or(DEFAULT_FILE_NAME)
+            }
+            PromptAction::GotoGeneration | PromptAction::Command => "",
+        };
+        self.prompt = Some((action, LineEditor::new(input)));
+        self.render_prompt()?;
+        self.writer.flush()
+    }
 
-        execute!(
+    fn handle_prompt_key(&mut self, code: KeyCode) -> Result<()> {
+        let (action, editor) = match &mut self.prompt {
+            Some(prompt) => prompt,
+            None => return Ok(()),
+        };
+        match editor.handle_key(code) {
+            Edit::Continue => {
+                self.render_prompt()?;
+                self.writer.flush()
+            }
+            Edit::Cancel => {
+                self.prompt = None;
+                self.render()
+            }
+            Edit::Submit(input) => {
+                let action = *action;
+                self.prompt = None;
+                let result = match action {
+                    PromptAction::Save => self.save(&input),
+                    PromptAction::Load => self.load(&input),
+                    PromptAction::GotoGeneration => self.goto_generation(&input)?,
+                    PromptAction::Command => self.run_command(&input),
+                };
+                self.render()?;
+                match result {
+                    Ok(message) | Err(message) => self.toast(message),
+                }
+            }
+        }
+    }
+
+    /// Draws what's being typed over the bottom row of the screen,
+    /// scrolled to keep the cursor in view
+    fn render_prompt(&mut self) -> Result<()> {
+        let (action, editor) = match &self.prompt {
+            Some(prompt) => prompt,
+            None => return Ok(()),
+        };
+        let label = match action {
+            PromptAction::Save => "Save as: ",
+            PromptAction::Load => "Load: ",
+            PromptAction::GotoGeneration => "Go to generation: ",
+            PromptAction::Command => ":",
+        };
+        let (width, height) = self.screen;
+        let (input, column) = editor.visible((width as usize).saturating_sub(label.len()));
+        let (before, after) = input.split_at(
+            input
+                .char_indices()
+                .nth(column)
+                .map_or(input.len(), |(i, _)| i),
+        );
+        let mut after = after.chars();
+        let under_cursor = after.next().unwrap_or(' ').to_string();
+        queue!(
             self.writer,
-            cursor::MoveTo(x, y),
-            style::PrintStyledContent(" ".on_white())
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            style::ResetColor,
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            style::Print(label),
+            style::Print(before),
+            style::PrintStyledContent(under_cursor.reverse()),
+            style::Print(after.as_str())
         )
-        .unwrap();
+    }
 
-        Some(())
+    /// Runs a command typed in the command palette. Returns the message to
+    /// show either way.
+    fn run_command(&mut self, input: &str) -> std::result::Result<String, String> {
+        match input.parse()? {
+            Command::Clear => {
+                let (width, height) = self.game.size();
+                self.game
+                    .clear_region((0, 0, width.saturating_sub(1), height.saturating_sub(1)));
+                // Nothing's left to lose
+                self.dirty = false;
+                Ok("Cleared the board".to_string())
+            }
+            Command::Random(density) => {
+                let seed = self.game.randomize(density.unwrap_or(cli::DEFAULT_DENSITY));
+                self.seed = Some(seed);
+                self.dirty = true;
+                Ok(format!("Filled randomly with seed {}", seed))
+            }
+            Command::Save(file) => {
+                let file = file
+                    .or_else(|| self.last_file.clone())
+                    .unwrap_or_else(|| DEFAULT_FILE_NAME.to_string());
+                self.save(&file)
+            }
+            Command::Load(file) => self.load(&file),
+            Command::Rule(rule) => {
+                self.game.set_rule(rule);
+                Ok(format!("Rule {}", self.game.rule()))
+            }
+            Command::Fps(fps) => {
+                self.fps = fps;
+                Ok(format!("{} generations per second", fps))
+            }
+            Command::Record(file) => {
+                let run = self
+                    .run
+                    .as_ref()
+                    .filter(|run| run.revision == self.game.revision())
+                    .ok_or("Nothing to record, the board changed since it last ticked")?;
+                let recording = Recording {
+                    generations: self.game.generation() - run.generation,
+                    ..run.recording.clone()
+                };
+                std::fs::write(&file, recording.to_text())
+                    .map_err(|error| format!("Can't record {}: {}", file, error))?;
+                Ok(format!(
+                    "Recorded {} generations in {}",
+                    recording.generations, file
+                ))
+            }
+            Command::Ramp(ramp) => {
+                let message = format!("Decaying cells in {}", ramp.name());
+                self.set_ramp(ramp);
+                Ok(message)
+            }
+        }
     }
 
-    fn kill_cell_at_pos(&mut self, x: u16, y: u16) -> Option<()> {
-        self.game.kill_cell_at_pos(x, y)?;
+    /// Colours decaying cells along `ramp` from the next render on
+    fn set_ramp(&mut self, ramp: ColorRamp) {
+        self.ramp = ramp;
+    }
 
-        execute!(
-            self.writer,
-            cursor::MoveTo(x, y),
-            style::PrintStyledContent(" ".on_black())
-        )
-        .unwrap();
+    /// Writes the live cells to `file`, in the format going with its
+    /// extension. Returns the message to show either way.
+    fn save(&mut self, file: &str) -> std::result::Result<String, String> {
+        write_live_cells(file, &self.game)
+            .map_err(|error| format!("Can't save {}: {}", file, error))?;
+        self.dirty = false;
+        self.last_file = Some(file.to_string());
+        Ok(format!("Saved {}", file))
+    }
 
-        Some(())
+    /// Replaces the board with the pattern in `file`, in the middle of it.
+    /// Files without a known extension are tried as RLE then plaintext.
+    fn load(&mut self, file: &str) -> std::result::Result<String, String> {
+        let (pattern, rule) = read_pattern(file)?;
+        if let Some(rule) = rule {
+            self.game.set_rule(rule);
+        }
+        let fits = self.replace_with(&pattern);
+        self.remember_pattern(file, pattern);
+        self.last_file = Some(file.to_string());
+        Ok(if fits {
+            format!("Loaded {}", file)
+        } else {
+            format!("Loaded {}, cut to fit the board", file)
+        })
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    terminal::enable_raw_mode()?;
+    /// Clears the board and stamps `pattern` in its middle, growing the board
+    /// to fit it unless its size is fixed. `false` if it had to be cut.
+    fn replace_with(&mut self, pattern: &Pattern) -> bool {
+        if self.world_size.is_none() {
+            let (width, height) = pattern.size();
+            self.game.resize_if_larger(width, height);
+        }
+        let (width, height) = self.game.size();
+        self.game
+            .clear_region((0, 0, width.saturating_sub(1), height.saturating_sub(1)));
+        let fits = self.game.stamp_centered(pattern);
+        self.dirty = false;
+        fits
+    }
+
+    /// Adds a pattern read from `file` to the picker, in place of the one
+    /// read from a file of the same name before
+    fn remember_pattern(&mut self, file: &str, pattern: Pattern) {
+        let name = Path::new(file).file_name().map_or_else(
+            || file.to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        self.loaded_patterns.retain(|(loaded, _)| *loaded != name);
+        self.loaded_patterns.push((name, pattern));
+    }
+
+    /// Ticks until the generation typed in, with a progress bar on the bottom
+    /// row. Returns the message to show.
+    fn goto_generation(&mut self, input: &str) -> Result<std::result::Result<String, String>> {
+        let target: u64 = match input.trim().parse() {
+            Ok(target) => target,
+            Err(_) => return Ok(Err(format!("Not a generation: {:?}", input))),
+        };
+        let start = self.game.generation();
+        if target <= start {
+            return Ok(Err(format!("Already at generation {}", start)));
+        }
+
+        let (width, height) = self.screen;
+        let mut last_drawn: Option<std::time::Instant> = None;
+        let mut drawn = Ok(());
+        self.note_run_start();
+        let color = self.color;
+        let writer = &mut self.writer;
+        self.game.tick_to(target, |generation| {
+            let now = std::time::Instant::now();
+            if drawn.is_err() || last_drawn.is_some_and(|last| now - last < PROGRESS_INTERVAL) {
+                return;
+            }
+            last_drawn = Some(now);
+            let bar = progress_bar(generation - start, target - start, width);
+            drawn = execute!(
+                writer,
+                cursor::MoveTo(0, height.saturating_sub(1)),
+                style::PrintStyledContent(styled(bar.black().on_grey(), color))
+            );
+        });
+        drawn?;
+        self.end_run_tick();
+
+        self.follow_origin();
+        if self.following {
+            self.follow_pattern();
+        }
+        Ok(Ok(format!("At generation {}", target)))
+    }
+
+    /// Every action with its keys, from the keymap, then the mouse and the
+    /// keys that aren't actions
+    fn help_lines(&self) -> Vec<String> {
+        let mut rows: Vec<(String, &str)> = Action::ALL
+            .iter()
+            .map(|&action| (self.keymap.keys(action).join(", "), action.description()))
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect();
+        let mouse_rows: &[_] = if self.mouse_capture {
+            &[
+                ("Left click", "Revive cells"),
+                ("Right click", "Kill cells"),
+                ("Middle click", "Toggle cells"),
+                ("Shift+drag", "Select cells"),
+                ("Scroll", "Change speed, or the brush with Ctrl"),
+            ]
+        } else {
+            &[("No mouse", "Edit cells with the keyboard cursor")]
+        };
+        rows.extend(
+            [
+                ("Shift+Arrows", "Move the view"),
+                ("1 to 9", "Stamp a pattern (r/f/F turn it, Esc stops)"),
+            ]
+            .iter()
+            .chain(mouse_rows)
+            .map(|&(keys, description)| (keys.to_string(), description)),
+        );
+
+        let width = rows.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+        let mut lines = vec!["Keys".to_string()];
+        lines.extend(
+            rows.iter()
+                .map(|(keys, description)| format!("  {:<2$}  {}", keys, description, width)),
+        );
+        lines.push(String::new());
+        lines.push("Press any key to close".to_string());
+        lines
+    }
 
-    let mut stdout = stdout();
-    execute!(
-        stdout,
-        terminal::EnterAlternateScreen,
-        event::EnableMouseCapture
-    )?;
+    /// Draws the help in a box in the middle of the screen, cut short if the
+    /// screen is too small for it
+    fn render_help(&mut self) -> Result<()> {
+        let (width, height) = self.screen;
+        let lines = self.help_lines();
+        let box_width = lines
+            .iter()
+            .map(|line| line.chars().count() + 2)
+            .max()
+            .unwrap_or(0)
+            .min(width as usize);
+        let box_height = (lines.len() + 2).min(height as usize);
+        let left = (width as usize - box_width) / 2;
+        let top = (height as usize - box_height) / 2;
 
-    TuiGame::new(&mut stdout).run().await?;
+        let padding = std::iter::once(String::new());
+        let lines = padding.clone().chain(lines).chain(padding);
+        for (row, line) in lines.take(box_height).enumerate() {
+            let line: String = format!(" {:<1$}", line, box_width)
+                .chars()
+                .take(box_width)
+                .collect();
+            let line = styled(line.white().on_dark_blue(), self.color);
+            queue!(
+                self.writer,
+                cursor::MoveTo(left as u16, (top + row) as u16),
+                style::PrintStyledContent(line)
+            )?;
+        }
+        Ok(())
+    }
 
-    execute!(
-        stdout,
-        terminal::LeaveAlternateScreen,
-        event::DisableMouseCapture
-    )?;
+    /// Patterns the picker offers, with their names: the built-in ones, then
+    /// the ones loaded from files
+    fn pattern_library(&self) -> Vec<(String, Pattern)> {
+        Pattern::preset_names()
+            .filter_map(|name| Some((name.to_string(), Pattern::preset(name)?)))
+            .chain(self.loaded_patterns.iter().cloned())
+            .collect()
+    }
 
-    terminal::disable_raw_mode()
+    /// Pauses and lists the patterns over the board
+    fn open_picker(&mut self) -> Result<()> {
+        self.playing = false;
+        self.picker = Some(Picker::default());
+        self.render()
+    }
+
+    fn handle_picker_key(&mut self, code: KeyCode) -> Result<()> {
+        let library = self.pattern_library();
+        let picker = match &mut self.picker {
+            Some(picker) => picker,
+            None => return Ok(()),
+        };
+        let last = library.len().saturating_sub(1);
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::PageUp => picker.selected = picker.selected.saturating_sub(PICKER_PAGE),
+            KeyCode::PageDown => picker.selected = (picker.selected + PICKER_PAGE).min(last),
+            KeyCode::Home => picker.selected = 0,
+            KeyCode::End => picker.selected = last,
+            KeyCode::Enter => {
+                let selected = picker.selected;
+                self.close_picker()?;
+                if let Some((name, pattern)) = library.into_iter().nth(selected) {
+                    return self.start_placing(&name, pattern);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.close_picker()?,
+            _ => return Ok(()),
+        }
+        self.render()
+    }
+
+    /// Clears the screen too, as the board may not reach under all of the
+    /// picker
+    fn close_picker(&mut self) -> Result<()> {
+        self.picker = None;
+        queue!(self.writer, terminal::Clear(terminal::ClearType::All))
+    }
+
+    /// Name and size of each pattern, one per line
+    fn picker_lines(&self) -> Vec<String> {
+        let library = self.pattern_library();
+        let width = library
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        library
+            .iter()
+            .map(|(name, pattern)| {
+                let (pattern_width, pattern_height) = pattern.size();
+                format!("{:<3$}  {}x{}", name, pattern_width, pattern_height, width)
+            })
+            .collect()
+    }
+
+    /// Draws the picker in a box in the middle of the screen, scrolled to keep
+    /// the selected pattern in view
+    fn render_picker(&mut self) -> Result<()> {
+        let (width, height) = self.screen;
+        let lines = self.picker_lines();
+        let picker = match &mut self.picker {
+            Some(picker) => picker,
+            None => return Ok(()),
+        };
+        // The title and a blank line above the list, and one below it
+        let visible = (height as usize).saturating_sub(3).min(lines.len()).max(1);
+        picker.scroll(visible);
+        let (selected, top) = (picker.selected, picker.top);
+
+        let box_width = lines
+            .iter()
+            .map(|line| line.chars().count() + 4)
+            .max()
+            .unwrap_or(0)
+            .max("Patterns".len() + 2)
+            .min(width as usize);
+        let box_height = (visible + 3).min(height as usize);
+        let left = (width as usize - box_width) / 2;
+        let box_top = (height as usize - box_height) / 2;
+
+        let rows = std::iter::once(("Patterns".to_string(), false))
+            .chain(std::iter::once((String::new(), false)))
+            .chain(
+                lines
+                    .into_iter()
+                    .enumerate()
+                    .skip(top)
+                    .take(visible)
+                    .map(|(index, line)| (format!(" {}", line), index == selected)),
+            )
+            .chain(std::iter::once((String::new(), false)));
+        for (row, (line, highlighted)) in rows.take(box_height).enumerate() {
+            let line: String = format!(" {:<1$}", line, box_width)
+                .chars()
+                .take(box_width)
+                .collect();
+            let line = if highlighted {
+                line.black().on_white()
+            } else {
+                line.white().on_dark_blue()
+            };
+            let line = styled(line, self.color);
+            queue!(
+                self.writer,
+                cursor::MoveTo(left as u16, (box_top + row) as u16),
+                style::PrintStyledContent(line)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the cells on screen as lines of `O` for live cells and `.` for
+    /// the others, without escape codes, so the output is easy to check.
+    #[cfg(test)]
+    fn render_plain(&mut self) -> Result<()> {
+        let (width, height) = self.board_view_size();
+        self.clamp_viewport(width, height);
+        let (left, top) = self.viewport;
+        let (board_width, board_height) = self.game.size();
+        let right = board_width.min(left.saturating_add(width));
+        let bottom = board_height.min(top.saturating_add(height));
+        for y in top..bottom {
+            let line: String = (left..right)
+                .map(|x| match self.game.find_cell_at_pos(x, y) {
+                    Some(cell) if cell.is_alive() => 'O',
+                    _ => '.',
+                })
+                .collect();
+            writeln!(self.writer, "{}", line)?;
+        }
+        self.writer.flush()
+    }
+
+    /// Where the view is on the board, shown when the board doesn't fit on
+    /// screen
+    fn position_indicator(&self) -> Option<String> {
+        let (board_width, board_height) = self.game.size();
+        let (width, height) = self.board_view_size();
+        if board_width <= width && board_height <= height {
+            return None;
+        }
+        let (x, y) = self.viewport;
+        Some(format!(
+            "x:{} y:{} / {}x{}",
+            x, y, board_width, board_height
+        ))
+    }
+
+    /// Top-left corner of the position indicator, in the bottom-right corner
+    /// of the screen
+    fn position_indicator_pos(&self, indicator: &str) -> (u16, u16) {
+        let (width, height) = self.view_size();
+        (
+            width.saturating_sub(indicator.len() as u16),
+            height.saturating_sub(1),
+        )
+    }
+
+    fn render_position_indicator(&mut self) -> Result<()> {
+        if let Some(indicator) = self.position_indicator() {
+            let (column, row) = self.position_indicator_pos(&indicator);
+            let indicator = styled(indicator.black().on_grey(), self.color);
+            queue!(
+                self.writer,
+                cursor::MoveTo(column, row),
+                style::PrintStyledContent(indicator)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Play state, generation, population, speed, rule and how often the
+    /// screen is rendered, followed by the `extra_status`
+    fn status_bar(&self) -> String {
+        let mut parts = vec![
+            self.play_state().to_string(),
+            format!("gen {}", self.game.generation()),
+            format!("pop {}", self.game.population()),
+            self.speed_status(),
+            self.game.rule().to_string(),
+            format!("{} edges", self.game.topology().name()),
+        ];
+        if let Some(seed) = self.seed {
+            parts.push(format!("seed {}", seed));
+        }
+        if let Some(frame_rate) = self.frame_rate.rate() {
+            parts.push(format!("{:.1} renders/s", frame_rate));
+        }
+        parts.extend(self.extra_status());
+        format!(" {} ", parts.join(" | "))
+    }
+
+    fn play_state(&self) -> &'static str {
+        if self.playing {
+            "playing"
+        } else {
+            "paused"
+        }
+    }
+
+    /// Generation, population and play state, for the terminal title
+    fn title(&self) -> String {
+        format!(
+            "Game of Life — gen {}, pop {} [{}]",
+            self.game.generation(),
+            self.game.population(),
+            self.play_state()
+        )
+    }
+
+    fn toggle_mouse(&mut self) -> Result<()> {
+        if !self.mouse_capture {
+            self.capture_mouse()?;
+            if self.mouse_capture {
+                self.toast("Mouse on")?;
+            }
+            return Ok(());
+        }
+        execute!(self.writer, event::DisableMouseCapture)?;
+        self.mouse_capture = false;
+        let message = format!(
+            "Mouse off, text can be selected. {} edits cells with the keyboard",
+            self.keymap.keys(Action::ToggleCursor).join("/")
+        );
+        self.toast(message)
+    }
+
+    /// Asks the terminal for mouse events. Not getting them is only warned
+    /// about, cells can be edited with the keyboard cursor too.
+    fn capture_mouse(&mut self) -> Result<()> {
+        match execute!(self.writer, event::EnableMouseCapture) {
+            Ok(()) => {
+                self.mouse_capture = true;
+                Ok(())
+            }
+            Err(error) => {
+                log::warn!("can't capture the mouse: {}", error);
+                self.mouse_capture = false;
+                self.warn_no_mouse()
+            }
+        }
+    }
+
+    fn warn_no_mouse(&mut self) -> Result<()> {
+        let message = format!(
+            "No mouse in this terminal, {} edits cells with the keyboard",
+            self.keymap.keys(Action::ToggleCursor).join("/")
+        );
+        self.toast(message)
+    }
+
+    /// Stops the game until `fg`, with the terminal usable by the shell
+    /// meanwhile, then redraws everything
+    #[cfg(unix)]
+    fn suspend(&mut self) -> Result<()> {
+        guard::suspend(&mut self.writer)?;
+        if self.mouse_capture {
+            self.capture_mouse()?;
+        }
+        self.title_since = None;
+        self.update_title(std::time::Instant::now())?;
+        execute!(
+            self.writer,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All)
+        )?;
+        self.render()
+    }
+
+    #[cfg(not(unix))]
+    fn suspend(&mut self) -> Result<()> {
+        self.toast("Suspending needs a Unix shell")
+    }
+
+    /// Sets the terminal title, at most once every `TITLE_INTERVAL` so it
+    /// isn't rewritten every frame
+    fn update_title(&mut self, now: std::time::Instant) -> Result<()> {
+        if self
+            .title_since
+            .is_some_and(|since| now.duration_since(since) < TITLE_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.title_since = Some(now);
+        let title = self.title();
+        execute!(self.writer, terminal::SetTitle(title))
+    }
+
+    /// The fps asked for, and while playing how many ticks there actually were
+    /// in the last second
+    fn speed_status(&self) -> String {
+        match self.tick_rate.rate().filter(|_| self.playing) {
+            Some(actual) => format!("target {:.1} / actual {:.1} fps", self.fps, actual),
+            None => format!("{:.1} fps", self.fps),
+        }
+    }
+
+    fn render_status_bar(&mut self) -> Result<()> {
+        if !self.show_status_bar {
+            return Ok(());
+        }
+        let (width, height) = self.screen;
+        let bar: String = format!("{:<1$}", self.status_bar(), width as usize)
+            .chars()
+            .take(width as usize)
+            .collect();
+        let bar = styled(bar.black().on_grey(), self.color);
+        queue!(
+            self.writer,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            style::PrintStyledContent(bar)
+        )
+    }
+
+    fn play_indicator(&self) -> &'static str {
+        if self.playing {
+            PLAYING_INDICATOR
+        } else {
+            PAUSED_INDICATOR
+        }
+    }
+
+    /// Always drawn, even without the status bar, so a paused board can't be
+    /// mistaken for a settled one
+    fn render_play_indicator(&mut self) -> Result<()> {
+        let indicator = styled(self.play_indicator().black().on_grey(), self.color);
+        queue!(
+            self.writer,
+            cursor::MoveTo(0, 0),
+            style::PrintStyledContent(indicator)
+        )
+    }
+
+    /// Redraws the status bar and the play indicator, after a change that
+    /// doesn't need a full render
+    fn refresh_status_bar(&mut self) -> Result<()> {
+        self.render_play_indicator()?;
+        self.render_bottom_row()?;
+        self.writer.flush()
+    }
+
+    /// Shown in the bottom-left corner when the status bar is hidden
+    fn status_label(&self) -> Option<String> {
+        let parts = self.extra_status();
+        if self.show_status_bar || parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" | "))
+        }
+    }
+
+    /// The pattern being placed, and in adaptive mode how long ticks take and
+    /// the fps that allows
+    fn extra_status(&self) -> Vec<String> {
+        let mut parts = vec![];
+        if let Some(placing) = &self.placing {
+            parts.push(format!("placing {}", placing.name));
+        }
+        if let Some(index) = self.paint_color {
+            parts.push(format!("drawing {}", PAINT_COLORS[index].0));
+        }
+        if self.adaptive {
+            let tick_time = match self.tick_time {
+                Some(tick_time) => format!("{:.1}ms", tick_time.as_secs_f64() * 1000.0),
+                None => "-".to_string(),
+            };
+            let fps = 1.0 / self.frame_time().as_secs_f32();
+            parts.push(format!("tick: {}, {:.1} fps", tick_time, fps));
+        }
+        parts
+    }
+
+    fn render_status_label(&mut self) -> Result<()> {
+        if let Some(label) = self.status_label() {
+            let row = self.view_size().1.saturating_sub(1);
+            let label = styled(label.black().on_grey(), self.color);
+            queue!(
+                self.writer,
+                cursor::MoveTo(0, row),
+                style::PrintStyledContent(label)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether something is drawn over the board at this screen position, so
+    /// the cell under it is neither painted nor editable
+    fn is_covered(&self, column: u16, row: u16) -> bool {
+        let on_indicator = match self.position_indicator() {
+            Some(indicator) => {
+                let (start, indicator_row) = self.position_indicator_pos(&indicator);
+                row == indicator_row && column >= start
+            }
+            None => false,
+        };
+        let on_status = self.status_label().is_some_and(|label| {
+            row == self.view_size().1.saturating_sub(1) && column < label.len() as u16
+        });
+        let on_play_indicator = row == 0 && column < self.play_indicator().chars().count() as u16;
+        let on_status_bar = row >= self.view_size().1;
+        let on_generation = self
+            .generation_counter()
+            .is_some_and(|(_, start, counter_row)| row == counter_row && column >= start);
+        let on_toast = !self.toasts.is_empty() && row + 1 >= self.screen.1;
+        on_indicator
+            || on_play_indicator
+            || on_status
+            || on_status_bar
+            || on_toast
+            || on_generation
+            || self.in_minimap(column, row)
+    }
+
+    /// Screen area of the minimap in the top-right corner, as
+    /// `(column, row, width, height)`
+    fn minimap_area(&self) -> Option<(u16, u16, u16, u16)> {
+        if !self.show_minimap {
+            return None;
+        }
+        let (width, height) = self.view_size();
+        let max_size = (
+            MINIMAP_MAX_SIZE.0.min(width / 3),
+            MINIMAP_MAX_SIZE.1.min(height / 3),
+        );
+        let (minimap_width, minimap_height) = Minimap::size_for(self.game.size(), max_size);
+        Some((
+            width.saturating_sub(minimap_width),
+            0,
+            minimap_width,
+            minimap_height,
+        ))
+    }
+
+    /// The generation counter and the screen position of its left end
+    fn generation_counter(&self) -> Option<(String, u16, u16)> {
+        if !self.show_generation {
+            return None;
+        }
+        let label = format!(" gen {} ", self.game.generation());
+        let row = self
+            .minimap_area()
+            .map_or(0, |(_, top, _, height)| top + height);
+        let column = self.view_size().0.saturating_sub(label.len() as u16);
+        Some((label, column, row))
+    }
+
+    /// Drawn over the board on every render, which also repaints the cells a
+    /// longer label covered before
+    fn render_generation_counter(&mut self) -> Result<()> {
+        if let Some((label, column, row)) = self.generation_counter() {
+            let label = styled(label.black().on_grey(), self.color);
+            queue!(
+                self.writer,
+                cursor::MoveTo(column, row),
+                style::PrintStyledContent(label)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn in_minimap(&self, column: u16, row: u16) -> bool {
+        match self.minimap_area() {
+            Some((left, top, width, height)) => {
+                column >= left && column - left < width && row >= top && row - top < height
+            }
+            None => false,
+        }
+    }
+
+    /// The minimap and its top-left corner on screen, only rebuilt when the
+    /// board changed since the last time
+    fn build_minimap(&mut self) -> Option<(&Minimap, u16, u16)> {
+        let (left, top, width, height) = self.minimap_area()?;
+        let max_size = (width, height);
+        let game = &self.game;
+        let minimap = match self.minimap.take() {
+            Some(minimap) if minimap.is_current(game, max_size) => minimap,
+            _ => Minimap::new(game, max_size),
+        };
+        Some((self.minimap.insert(minimap), left, top))
+    }
+
+    fn render_minimap(&mut self) -> Result<()> {
+        let (view_width, view_height) = self.board_view_size();
+        let (view_x, view_y) = self.viewport;
+        let color = self.color;
+        let (minimap, left, top) = match self.build_minimap() {
+            Some(minimap) => minimap,
+            None => return Ok(()),
+        };
+        let view_start = minimap.to_minimap_pos(view_x, view_y);
+        let view_end = minimap.to_minimap_pos(
+            view_x.saturating_add(view_width - 1),
+            view_y.saturating_add(view_height - 1),
+        );
+
+        let (width, height) = minimap.size();
+        let mut lines = vec![];
+        for row in 0..height {
+            let mut line = vec![];
+            for column in 0..width {
+                let in_view = (view_start.0..=view_end.0).contains(&column)
+                    && (view_start.1..=view_end.1).contains(&row);
+                let shade = minimap.shade(column, row).to_string();
+                line.push(match (in_view, color) {
+                    (true, true) => shade.white().on_dark_blue(),
+                    (false, true) => shade.white().on_dark_grey(),
+                    (true, false) => shade.reverse(),
+                    (false, false) => shade.stylize(),
+                });
+            }
+            lines.push(line);
+        }
+
+        for (row, line) in lines.into_iter().enumerate() {
+            queue!(self.writer, cursor::MoveTo(left, top + row as u16))?;
+            for content in line {
+                queue!(self.writer, style::PrintStyledContent(content))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Centres the view on the part of the board under a minimap position
+    fn jump_to_minimap_pos(&mut self, column: u16, row: u16) -> Result<()> {
+        let (minimap, left, top) = match self.build_minimap() {
+            Some(minimap) => minimap,
+            None => return Ok(()),
+        };
+        let (x, y) = minimap.to_board_pos(column - left, row - top);
+        let (block_width, block_height) = minimap.block();
+        let (x, y) = (x + block_width / 2, y + block_height / 2);
+        self.following = false;
+        self.centre_on((x, y, x, y));
+        self.render()
+    }
+
+    /// Queues a message to show on the bottom row for `TOAST_DURATION`, after
+    /// the ones already queued.
+    fn toast(&mut self, message: impl Into<String>) -> Result<()> {
+        let message = message.into();
+        log::debug!("toast: {}", message);
+        self.toasts.push_back(message);
+        if self.toasts.len() == 1 {
+            self.toast_since = Some(std::time::Instant::now());
+            self.render_toast()?;
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Moves on to the next message once the shown one has been there long
+    /// enough, repainting what it covered.
+    fn expire_toasts(&mut self, now: std::time::Instant) -> Result<()> {
+        match self.toast_since {
+            Some(since) if now.duration_since(since) >= TOAST_DURATION => {
+                self.toasts.pop_front();
+                self.toast_since = Some(now).filter(|_| !self.toasts.is_empty());
+                self.render()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn render_toast(&mut self) -> Result<()> {
+        if let Some(message) = self.toasts.front().cloned() {
+            self.show_message(&message)?;
+        }
+        Ok(())
+    }
+
+    /// The status bar, covered by the shown message if there's one and by the
+    /// quit confirmation while it's waiting for an answer
+    fn render_bottom_row(&mut self) -> Result<()> {
+        self.render_status_bar()?;
+        self.render_toast()?;
+        if self.pending_quit {
+            self.show_message(QUIT_PROMPT)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a message over the bottom row of the screen, to be flushed. It
+    /// stays there until the next render.
+    fn show_message(&mut self, message: &str) -> Result<()> {
+        let (_, height) = self.screen;
+        queue!(
+            self.writer,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            style::ResetColor,
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            style::Print(message)
+        )
+    }
+
+    /// Keeps the viewport inside the board, which is always at least as large
+    /// as the screen.
+    fn clamp_viewport(&mut self, width: u16, height: u16) {
+        let (board_width, board_height) = self.game.size();
+        self.viewport.0 = self.viewport.0.min(board_width.saturating_sub(width));
+        self.viewport.1 = self.viewport.1.min(board_height.saturating_sub(height));
+    }
+
+    fn to_board_pos(&self, column: u16, row: u16) -> (u16, u16) {
+        (
+            self.viewport.0.saturating_add(column / self.cell_width()),
+            self.viewport.1.saturating_add(row),
+        )
+    }
+
+    /// Enters cursor mode with the cursor in the middle of the screen, or
+    /// leaves it.
+    fn toggle_cursor(&mut self) -> Result<()> {
+        self.cursor = match self.cursor {
+            Some(_) => None,
+            None => {
+                let (width, height) = self.view_size();
+                Some(self.to_board_pos(width / 2, height / 2))
+            }
+        };
+        self.render()
+    }
+
+    fn move_cursor(&mut self, delta: (i32, i32)) -> Result<()> {
+        let (old_x, old_y) = match self.cursor {
+            Some(cursor) => cursor,
+            None => return Ok(()),
+        };
+        let (x, y) = move_cursor((old_x, old_y), delta, self.game.size(), self.cursor_wrap);
+        self.cursor = Some((x, y));
+
+        let viewport = self.viewport;
+        self.scroll_to(x, y);
+        // The pasted cells move along with the cursor
+        if self.viewport != viewport || self.placing.is_some() {
+            return self.render();
+        }
+        self.paint_cell(old_x, old_y)?;
+        self.paint_cell(x, y)?;
+        self.writer.flush()
+    }
+
+    /// Moves the view just enough for the given board cell to be on screen
+    fn scroll_to(&mut self, x: u16, y: u16) {
+        let (width, height) = self.board_view_size();
+        let (left, top) = &mut self.viewport;
+        if x < *left {
+            *left = x;
+        } else if x >= *left + width {
+            *left = x + 1 - width;
+        }
+        if y < *top {
+            *top = y;
+        } else if y >= *top + height {
+            *top = y + 1 - height;
+        }
+    }
+
+    fn render_cursor(&mut self) -> Result<()> {
+        match self.cursor {
+            Some((x, y)) => self.paint_cell(x, y),
+            None => Ok(()),
+        }
+    }
+
+    fn toggle_cell_under_cursor(&mut self) -> Result<()> {
+        if let Some((x, y)) = self.cursor {
+            let alive = self.game.find_cell_at_pos(x, y).is_some_and(Cell::is_alive);
+            if let Err(error) = self.set_board_cell(x, y, !alive) {
+                self.report(error)?;
+            }
+        }
+        self.writer.flush()
+    }
+
+    /// Position on screen of a board cell, if it's visible
+    fn to_screen_pos(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let column = x.checked_sub(self.viewport.0)?;
+        let row = y.checked_sub(self.viewport.1)?;
+        let (width, height) = self.board_view_size();
+        if column < width && row < height {
+            Some((column * self.cell_width(), row))
+        } else {
+            None
+        }
+    }
+
+    fn revive_cell_at_pos(&mut self, column: u16, row: u16) -> GameResult {
+        self.set_cell_at_pos(column, row, true)
+    }
+
+    fn kill_cell_at_pos(&mut self, column: u16, row: u16) -> GameResult {
+        self.set_cell_at_pos(column, row, false)
+    }
+
+    /// Kills the cells under the brush if the one under the pointer is alive,
+    /// otherwise revives them.
+    fn toggle_cell_at_pos(&mut self, column: u16, row: u16) -> GameResult {
+        let (x, y) = self.to_board_pos(column, row);
+        match self.game.find_cell_at_pos(x, y) {
+            Some(cell) => self.set_cell_at_pos(column, row, !cell.is_alive()),
+            None => Ok(()),
+        }
+    }
+
+    /// Revives or kills the cells under the brush at the given screen
+    /// position, along with their mirror images for the current symmetry.
+    /// Does nothing where something is drawn over the board.
+    fn set_cell_at_pos(&mut self, column: u16, row: u16, alive: bool) -> GameResult {
+        if self.is_covered(column, row) {
+            return Ok(());
+        }
+        let (x, y) = self.to_board_pos(column, row);
+        for (dx, dy) in brush_offsets(self.brush) {
+            let x = i32::from(x) + dx;
+            let y = i32::from(y) + dy;
+            if x >= 0 && y >= 0 {
+                self.set_board_cell(x as u16, y as u16, alive)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Revives or kills a board cell along with its mirror images for the
+    /// current symmetry, and draws them. Positions off the board, which the
+    /// brush reaches near the edges, are left alone.
+    fn set_board_cell(&mut self, x: u16, y: u16, alive: bool) -> GameResult {
+        let (width, height) = self.game.size();
+        if x >= width || y >= height {
+            return Ok(());
+        }
+
+        self.dirty = true;
+        for (x, y) in self.symmetry.positions(x, y, width, height) {
+            if alive {
+                self.game.revive_cell_at_pos(x, y)?;
+                // Drawing uncoloured leaves the colour of live cells alone
+                if let Some(index) = self.paint_color {
+                    self.game
+                        .set_cell_color(x, y, Some(PAINT_COLORS[index].1))?;
+                }
+            } else {
+                self.game.kill_cell_at_pos(x, y)?;
+            }
+            self.paint_cell(x, y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows what went wrong as a message, unless it was writing to the
+    /// terminal, which there's no going on after.
+    fn report(&mut self, error: GameError) -> Result<()> {
+        match error {
+            GameError::Io(error) => Err(error),
+            error => {
+                log::warn!("{}", error);
+                self.toast(error.to_string())
+            }
+        }
+    }
+
+    /// How the cell at `(x, y)` looks, with the cursor, the pasted cells or
+    /// the selection on top
+    fn styled_cell(&self, cell: &Cell, x: u16, y: u16) -> style::StyledContent<&'static str> {
+        if self.cursor == Some((x, y)) {
+            styled(cursor_content(cell.is_alive()), self.color)
+        } else if !self.color {
+            self.plain_cell(cell, x, y)
+        } else if self.is_placed(x, y) {
+            // A dim ghost, leaving the board as it is until placed
+            tinted_style(cell, self.game.rule(), &self.ramp, (90, 200, 90))
+        } else if self.is_selected(x, y) {
+            tinted_style(cell, self.game.rule(), &self.ramp, (40, 110, 255))
+        } else if let Some(born) = self.previewed_change(x, y) {
+            let tint = if born { (0, 200, 0) } else { (255, 0, 0) };
+            tinted_style(cell, self.game.rule(), &self.ramp, tint)
+        } else if self.on_grid(cell, x, y) {
+            "·".dark_grey().on_black()
+        } else {
+            cell_style(cell, self.game.rule(), &self.ramp)
+        }
+    }
+
+    /// A cell drawn with plain characters, for terminals without colour
+    fn plain_cell(&self, cell: &Cell, x: u16, y: u16) -> style::StyledContent<&'static str> {
+        let content = if self.is_placed(x, y) {
+            "o"
+        } else if let Some(born) = self.previewed_change(x, y) {
+            if born {
+                "+"
+            } else {
+                "x"
+            }
+        } else if cell.dying().is_some() {
+            "*"
+        } else if cell.is_alive() {
+            "O"
+        } else if self.on_grid(cell, x, y) {
+            "."
+        } else {
+            " "
+        };
+        match self.is_selected(x, y) {
+            true => content.reverse(),
+            false => content.stylize(),
+        }
+    }
+
+    /// With the preview on, whether the cell is about to be born (`true`) or
+    /// to die (`false`), `None` if it stays as it is
+    fn previewed_change(&self, x: u16, y: u16) -> Option<bool> {
+        let preview = self.preview.as_ref()?;
+        if preview.births.contains(&(x, y)) {
+            Some(true)
+        } else if preview.deaths.contains(&(x, y)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a grid dot goes on the cell: only dead ones, so it never hides
+    /// what's alive. Lines follow the board's origin, staying put as it grows.
+    fn on_grid(&self, cell: &Cell, x: u16, y: u16) -> bool {
+        let every = match self.grid_every {
+            Some(every) => i32::from(every),
+            None => return false,
+        };
+        let (origin_x, origin_y) = self.game.origin();
+        let on_line = |pos: u16, origin: u16| (i32::from(pos) - i32::from(origin)) % every == 0;
+        !cell.is_alive() && cell.dying().is_none() && (on_line(x, origin_x) || on_line(y, origin_y))
+    }
+
+    /// Selected rectangle in board coordinates, as left, top, right and bottom
+    /// edges (all inclusive)
+    fn selected_rect(&self) -> Option<(u16, u16, u16, u16)> {
+        self.selection.map(|selection| selection.rect())
+    }
+
+    fn is_selected(&self, x: u16, y: u16) -> bool {
+        self.selected_rect()
+            .is_some_and(|(left, top, right, bottom)| {
+                (left..=right).contains(&x) && (top..=bottom).contains(&y)
+            })
+    }
+
+    /// Copies the selected cells to the clipboard, and kills them if `cut`.
+    fn copy_selection(&mut self, cut: bool) -> Result<()> {
+        let bounds = match self.selected_rect() {
+            Some(bounds) => bounds,
+            None => return self.toast("Nothing selected, shift-drag to select"),
+        };
+        self.clipboard = Some(self.game.copy_region(bounds));
+        if cut {
+            self.dirty = true;
+            self.game.clear_region(bounds);
+            self.render()?;
+        }
+        Ok(())
+    }
+
+    /// Starts moving `pattern` around with the mouse or the cursor, starting
+    /// at the cursor or in the middle of the screen.
+    fn start_placing(&mut self, name: &str, pattern: Pattern) -> Result<()> {
+        let (width, height) = self.view_size();
+        let pos = self
+            .cursor
+            .unwrap_or_else(|| self.to_board_pos(width / 2, height / 2));
+        self.placing = Some(Placing {
+            name: name.to_string(),
+            pattern,
+            pos,
+        });
+        self.render()
+    }
+
+    fn move_placing(&mut self, column: u16, row: u16) {
+        let pos = self.to_board_pos(column, row);
+        if let Some(placing) = &mut self.placing {
+            placing.pos = pos;
+        }
+    }
+
+    /// Top-left corner of the pattern being placed: the cursor in cursor mode,
+    /// otherwise where the mouse last was
+    fn placing_origin(&self) -> Option<(u16, u16)> {
+        self.placing
+            .as_ref()
+            .map(|placing| self.cursor.unwrap_or(placing.pos))
+    }
+
+    /// Stamps the pattern being placed, adding to the live cells or replacing
+    /// everything under it if `overwrite`. Cells past the edges of the board
+    /// are dropped. Placing goes on so the pattern can be stamped again.
+    fn place(&mut self, overwrite: bool) -> Result<()> {
+        if let Some(((x, y), placing)) = self.placing_origin().zip(self.placing.as_ref()) {
+            self.dirty = true;
+            let fits = if overwrite {
+                self.game.stamp_overwriting(&placing.pattern, x, y)
+            } else {
+                self.game.stamp(&placing.pattern, x, y)
+            };
+            if !fits {
+                self.toast("Pattern cut at the edge of the board")?;
+            }
+        }
+        self.render()
+    }
+
+    /// Reads a pattern from the system clipboard, as RLE or plaintext, and
+    /// stamps it at the cursor, or starts placing it when there's no cursor.
+    fn paste_system(&mut self) -> Result<()> {
+        let mut pattern = match read_system_clipboard().and_then(|text| {
+            format::parse(&text).map_err(|error| format!("Not a pattern: {}", error))
+        }) {
+            Ok(pattern) => pattern,
+            Err(message) => return self.toast(message),
+        };
+        pattern.normalize();
+        match self.cursor {
+            Some((x, y)) => {
+                self.dirty = true;
+                self.game.stamp(&pattern, x, y);
+                self.render()
+            }
+            None => self.start_placing("pasted", pattern),
+        }
+    }
+
+    fn is_placed(&self, x: u16, y: u16) -> bool {
+        match self.placing_origin().zip(self.placing.as_ref()) {
+            Some(((left, top), placing)) => {
+                x >= left && y >= top && placing.pattern.is_alive(x - left, y - top)
+            }
+            None => false,
+        }
+    }
+
+    /// Board position under the screen position, moved inside the board when
+    /// the mouse is past its edges
+    fn to_board_pos_clamped(&self, column: u16, row: u16) -> (u16, u16) {
+        let (x, y) = self.to_board_pos(column, row);
+        let (width, height) = self.game.size();
+        (
+            x.min(width.saturating_sub(1)),
+            y.min(height.saturating_sub(1)),
+        )
+    }
+
+    fn paint_cell(&mut self, x: u16, y: u16) -> Result<()> {
+        let (column, row) = match self.to_screen_pos(x, y) {
+            Some(pos) => pos,
+            None => return Ok(()),
+        };
+        if (column..column + self.cell_width()).any(|column| self.is_covered(column, row)) {
+            return Ok(());
+        }
+        let cell = match self.game.find_cell_at_pos(x, y) {
+            Some(cell) => cell,
+            None => return Ok(()),
+        };
+        let content = self.styled_cell(cell, x, y);
+        let width = self.cell_width();
+        print_cell(&mut self.writer, (column, row), content, width)
+    }
+}
+
+/// Draws a cell at a screen position, repeated to fill the `width` characters
+/// it takes
+fn print_cell(
+    writer: &mut impl Write,
+    (column, row): (u16, u16),
+    content: style::StyledContent<&str>,
+    width: u16,
+) -> Result<()> {
+    queue!(writer, cursor::MoveTo(column, row))?;
+    for _ in 0..width {
+        queue!(writer, style::PrintStyledContent(content))?;
+    }
+    Ok(())
+}
+
+/// Moves `pos` by `delta` on a board of the given size. Past the edges the
+/// position either wraps around to the opposite edge or stays at the edge.
+fn move_cursor(pos: (u16, u16), delta: (i32, i32), size: (u16, u16), wrap: bool) -> (u16, u16) {
+    let step = |pos: u16, delta: i32, len: u16| {
+        let len = i32::from(len).max(1);
+        let pos = i32::from(pos) + delta;
+        let pos = if wrap {
+            pos.rem_euclid(len)
+        } else {
+            pos.clamp(0, len - 1)
+        };
+        pos as u16
+    };
+    (step(pos.0, delta.0, size.0), step(pos.1, delta.1, size.1))
+}
+
+/// Offsets of the cells covered by a disc shaped brush of the given radius,
+/// a radius of 0 being a single cell
+fn brush_offsets(radius: u16) -> Vec<(i32, i32)> {
+    let radius = i32::from(radius);
+    let mut offsets = vec![];
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            // The extra radius rounds the disc off so small ones aren't
+            // just plus signs
+            if dx * dx + dy * dy <= radius * radius + radius {
+                offsets.push((dx, dy));
+            }
+        }
+    }
+    offsets
+}
+
+/// Cells on the line from `from` to `to`, both included
+fn line(from: (u16, u16), to: (u16, u16)) -> Vec<(u16, u16)> {
+    // Bresenham's line algorithm
+    let (mut x, mut y) = (i32::from(from.0), i32::from(from.1));
+    let (to_x, to_y) = (i32::from(to.0), i32::from(to.1));
+    let dx = (to_x - x).abs();
+    let dy = -(to_y - y).abs();
+    let step_x = if x < to_x { 1 } else { -1 };
+    let step_y = if y < to_y { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut points = vec![];
+    loop {
+        points.push((x as u16, y as u16));
+        if (x, y) == (to_x, to_y) {
+            break;
+        }
+        let double_error = 2 * error;
+        if double_error >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if double_error <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+    points
+}
+
+/// Progress of demo mode through the built-in patterns
+struct Demo {
+    /// Index of the pattern being shown in `Pattern::preset_names`
+    pattern: usize,
+    /// Generations since the pattern was put on the board
+    generations: u32,
+}
+
+/// Counts events, like ticks or renders, to tell how many happen per second
+#[derive(Debug, Default)]
+struct RateMeter {
+    /// When the events of the last `RATE_WINDOW` happened, and always at least
+    /// the last two
+    times: VecDeque<std::time::Instant>,
+}
+
+impl RateMeter {
+    fn record(&mut self, now: std::time::Instant) {
+        self.times.push_back(now);
+        while self.times.len() > 2 && now - self.times[0] > RATE_WINDOW {
+            self.times.pop_front();
+        }
+    }
+
+    /// Events per second, averaged over the recorded ones
+    fn rate(&self) -> Option<f32> {
+        let span = *self.times.back()? - *self.times.front()?;
+        if span.is_zero() {
+            return None;
+        }
+        Some((self.times.len() - 1) as f32 / span.as_secs_f32())
+    }
+}
+
+/// Cells the next tick changes, as of a `Game::revision`
+struct Preview {
+    revision: u64,
+    births: HashSet<(u16, u16)>,
+    deaths: HashSet<(u16, u16)>,
+}
+
+impl Preview {
+    fn new(game: &Game) -> Self {
+        let (births, deaths) = game.changes_next_gen();
+        Self {
+            revision: game.revision(),
+            births: births.into_iter().collect(),
+            deaths: deaths.into_iter().collect(),
+        }
+    }
+}
+
+/// Start of the run of ticks the board is in
+struct Run {
+    recording: Recording,
+    /// Generation the run started at
+    generation: u64,
+    /// `Game::revision` after the last tick, to notice changes between ticks
+    revision: u64,
+}
+
+/// What the prompt at the bottom of the screen is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PromptAction {
+    Save,
+    Load,
+    GotoGeneration,
+    Command,
+}
+
+/// Position in the pattern picker's list
+#[derive(Debug, Default)]
+struct Picker {
+    selected: usize,
+    /// First pattern shown, when they don't all fit
+    top: usize,
+}
+
+impl Picker {
+    /// Moves `top` so the selected pattern is among the `visible` ones shown
+    fn scroll(&mut self, visible: usize) {
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + visible {
+            self.top = self.selected + 1 - visible;
+        }
+    }
+}
+
+/// Pattern being moved around before it's stamped on the board
+struct Placing {
+    /// Shown while placing, the preset name or "clipboard"
+    name: String,
+    pattern: Pattern,
+    /// Board position of the top-left corner of the pattern
+    pos: (u16, u16),
+}
+
+/// Rectangle of board cells between two corners picked with the mouse
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Selection {
+    /// Where the drag started
+    start: (u16, u16),
+    /// Where the drag is now, or ended
+    end: (u16, u16),
+}
+
+impl Selection {
+    /// Left, top, right and bottom edges, all inclusive
+    fn rect(&self) -> (u16, u16, u16, u16) {
+        (
+            self.start.0.min(self.end.0),
+            self.start.1.min(self.end.1),
+            self.start.0.max(self.end.0),
+            self.start.1.max(self.end.1),
+        )
+    }
+
+    /// Keeps the selection on the same cells after the board grew by `dx`
+    /// columns to the left and `dy` rows to the top
+    fn translate(&mut self, dx: u16, dy: u16) {
+        self.start = (self.start.0 + dx, self.start.1 + dy);
+        self.end = (self.end.0 + dx, self.end.1 + dy);
+    }
+}
+
+/// Mirror images painted along with each cell drawn with the mouse
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Symmetry {
+    None,
+    /// Mirrored left to right
+    Horizontal,
+    /// Mirrored top to bottom
+    Vertical,
+    /// Mirrored both left to right and top to bottom
+    Both,
+    /// Rotated by 90, 180 and 270 degrees around the centre of the board
+    FourFold,
+}
+
+impl Symmetry {
+    fn next(self) -> Self {
+        match self {
+            Symmetry::None => Symmetry::Horizontal,
+            Symmetry::Horizontal => Symmetry::Vertical,
+            Symmetry::Vertical => Symmetry::Both,
+            Symmetry::Both => Symmetry::FourFold,
+            Symmetry::FourFold => Symmetry::None,
+        }
+    }
+
+    /// Positions of `(x, y)` and its images on a `width` x `height` board.
+    /// Images that don't land exactly on a cell of the board are left out.
+    fn positions(self, x: u16, y: u16, width: u16, height: u16) -> Vec<(u16, u16)> {
+        let mirror_x = width - 1 - x;
+        let mirror_y = height - 1 - y;
+        let mut positions = match self {
+            Symmetry::None => vec![(x, y)],
+            Symmetry::Horizontal => vec![(x, y), (mirror_x, y)],
+            Symmetry::Vertical => vec![(x, y), (x, mirror_y)],
+            Symmetry::Both => vec![(x, y), (mirror_x, y), (x, mirror_y), (mirror_x, mirror_y)],
+            Symmetry::FourFold => {
+                // Doubled coordinates relative to the centre, so the centre of
+                // even sized boards doesn't fall between cells
+                let (width, height) = (i32::from(width), i32::from(height));
+                let dx = 2 * i32::from(x) - (width - 1);
+                let dy = 2 * i32::from(y) - (height - 1);
+                let to_cell = |dx: i32, dy: i32| {
+                    let (x, y) = (dx + width - 1, dy + height - 1);
+                    let on_cell = x % 2 == 0 && y % 2 == 0;
+                    let inside = (0..2 * width).contains(&x) && (0..2 * height).contains(&y);
+                    if on_cell && inside {
+                        Some(((x / 2) as u16, (y / 2) as u16))
+                    } else {
+                        None
+                    }
+                };
+                [(dx, dy), (-dy, dx), (-dx, -dy), (dy, -dx)]
+                    .iter()
+                    .filter_map(|&(dx, dy)| to_cell(dx, dy))
+                    .collect()
+            }
+        };
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+}
+
+fn cell_style(cell: &Cell, rule: &Rule, ramp: &ColorRamp) -> style::StyledContent<&'static str> {
+    match cell.dying() {
+        Some(left) => " ".on(dying_color(left, rule.states(), ramp)),
+        None if cell.is_alive() => " ".on(live_color(cell)),
+        None => " ".on_black(),
+    }
+}
+
+/// The colour the cell was painted or born with, otherwise one for its age
+fn live_color(cell: &Cell) -> style::Color {
+    match cell.color() {
+        Some(Color { r, g, b }) => style::Color::Rgb { r, g, b },
+        None => age_color(cell.age()),
+    }
+}
+
+/// Dims live cells from white to grey as they get older
+fn age_color(age: u32) -> style::Color {
+    let fraction = age.min(AGE_RAMP) as f32 / AGE_RAMP as f32;
+    let level = (255.0 - 145.0 * fraction) as u8;
+    style::Color::Rgb {
+        r: level,
+        g: level,
+        b: level,
+    }
+}
+
+/// Cell style mixed half and half with `tint`, keeping live, dead and
+/// decaying cells apart
+fn tinted_style(
+    cell: &Cell,
+    rule: &Rule,
+    ramp: &ColorRamp,
+    tint: (u8, u8, u8),
+) -> style::StyledContent<&'static str> {
+    let (r, g, b) = match cell.dying() {
+        Some(left) => match dying_color(left, rule.states(), ramp) {
+            style::Color::Rgb { r, g, b } => (r, g, b),
+            _ => (0, 0, 0),
+        },
+        None if cell.is_alive() => match live_color(cell) {
+            style::Color::Rgb { r, g, b } => (r, g, b),
+            _ => (255, 255, 255),
+        },
+        None => (0, 0, 0),
+    };
+    let mix = |channel: u8, tint: u8| ((u16::from(channel) + u16::from(tint)) / 2) as u8;
+    " ".on(style::Color::Rgb {
+        r: mix(r, tint.0),
+        g: mix(g, tint.1),
+        b: mix(b, tint.2),
+    })
+}
+
+/// Moves decaying cells down `ramp` as they get closer to dead
+fn dying_color(left: u8, states: u8, ramp: &ColorRamp) -> style::Color {
+    ramp.at(f32::from(left.saturating_sub(1)) / f32::from(states.saturating_sub(2).max(1)))
+}
+
+#[cfg(feature = "clipboard")]
+fn read_system_clipboard() -> std::result::Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|error| format!("Can't read the clipboard: {}", error))
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn read_system_clipboard() -> std::result::Result<String, String> {
+    Err("Built without clipboard support, see the `clipboard` feature".to_string())
+}
+
+/// `done` out of `total` as a line `width` columns wide, like
+/// ` 3/10 [###.......] `
+fn progress_bar(done: u64, total: u64, width: u16) -> String {
+    let label = format!(" {}/{} ", done, total);
+    let bar_width = (width as usize).saturating_sub(label.len() + 3);
+    let filled = (bar_width as u64 * done / total.max(1)) as usize;
+    let bar = format!(
+        "{}[{}{}] ",
+        label,
+        "#".repeat(filled),
+        ".".repeat(bar_width - filled)
+    );
+    bar.chars().take(width as usize).collect()
+}
+
+/// The next event if there's one waiting, without waiting for one
+async fn ready_event(reader: &mut EventStream) -> Option<Result<Event>> {
+    std::future::poll_fn(|context| match Pin::new(&mut *reader).poll_next(context) {
+        Poll::Ready(event) => Poll::Ready(event),
+        Poll::Pending => Poll::Ready(None),
+    })
+    .await
+}
+
+fn frame_interval(frame_time: Duration) -> time::Interval {
+    time::interval_at(Instant::now() + frame_time, frame_time)
+}
+
+/// Time between ticks at `fps`, which is kept at `MIN_FPS` or more
+fn tick_interval(fps: f32) -> Duration {
+    Duration::from_secs_f32(1.0 / fps.max(MIN_FPS))
+}
+
+/// Highest fps up to `fps` that leaves time to render between ticks taking
+/// `tick_time`
+fn adaptive_fps(fps: f32, tick_time: Duration) -> f32 {
+    let max_fps = ADAPTIVE_TICK_SHARE / tick_time.as_secs_f32().max(f32::EPSILON);
+    fps.min(max_fps)
+}
+
+fn midpoint(a: u16, b: u16) -> u16 {
+    ((u32::from(a) + u32::from(b)) / 2) as u16
+}
+
+/// `content` as it's drawn: as is in colour, otherwise without its colours
+/// and with light backgrounds, like the bars', as reversed text
+fn styled<D: std::fmt::Display>(
+    mut content: style::StyledContent<D>,
+    color: bool,
+) -> style::StyledContent<D> {
+    if !color {
+        let style = content.style_mut();
+        style.foreground_color = None;
+        if let Some(style::Color::Grey | style::Color::White) = style.background_color.take() {
+            style.attributes.set(style::Attribute::Reverse);
+        }
+    }
+    content
+}
+
+fn cursor_content(alive: bool) -> style::StyledContent<&'static str> {
+    match alive {
+        true => "+".black().on_white(),
+        false => "+".white().on_black(),
+    }
+}
+
+/// Cursor movement for arrows and hjkl, shifted or not
+fn cursor_delta(code: KeyCode) -> Option<(i32, i32)> {
+    match code {
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('H') => Some((-1, 0)),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('L') => Some((1, 0)),
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('K') => Some((0, -1)),
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('J') => Some((0, 1)),
+        _ => None,
+    }
+}
+
+fn is_arrow(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", cli::usage());
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, cli::usage());
+            std::process::exit(2);
+        }
+    };
+    let log_target = options.log_target(std::env::var("GAMEOFLIFE_LOG").ok().as_deref());
+    let started = match log_target {
+        Ok(Some((file, level))) => logger::init(&file, level)
+            .map_err(|error| format!("can't write the log to {}: {}", file, error)),
+        Ok(None) => Ok(()),
+        Err(err) => Err(err),
+    };
+    if let Err(err) = started {
+        eprintln!("error: {}", err);
+        std::process::exit(2);
+    }
+    log::info!("starting with {:?}", options);
+
+    if options.bench {
+        if let Err(err) = headless::bench(&options, &mut stdout().lock()) {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Read before entering the alternate screen, which would hide the error
+    let pattern = match (&options.pattern, options.format) {
+        (Some(file), _) => Some(read_pattern(file)),
+        (None, Some(format)) => Some(read_piped_pattern(format)),
+        (None, None) => None,
+    };
+    let pattern = match pattern.transpose() {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(2);
+        }
+    };
+    let replay = match options.replay.as_deref().map(read_recording).transpose() {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(2);
+        }
+    };
+    if options.headless {
+        if let Err(err) = headless::run(&options, pattern, &mut stdout().lock()) {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let keymap = match Keymap::load() {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(2);
+        }
+    };
+
+    // Checked before raw mode, whose error would say much less
+    if let Err(err) = check_terminal(stdin().is_terminal(), stdout().is_terminal()) {
+        eprintln!("error: {}", err);
+        std::process::exit(2);
+    }
+    let color = options.color.enabled(
+        std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+        std::env::var("TERM").ok().as_deref(),
+        stdout().is_terminal(),
+    );
+    let mut guard = TerminalGuard::enter(stdout())?;
+    let mouse_captured = guard.mouse_captured();
+    let mut tui = TuiGame::new(&mut guard, &options, keymap);
+    tui.color = color;
+    if !mouse_captured {
+        tui.mouse_capture = false;
+        tui.warn_no_mouse()?;
+    }
+    if let (Some((pattern, rule)), Some(file)) = (pattern, &options.pattern) {
+        if let Some(warning) = apply_file_rule(&mut tui.game, &options, file, rule) {
+            tui.toast(warning)?;
+        }
+        tui.replace_with(&pattern);
+        tui.remember_pattern(file, pattern);
+        tui.last_file = Some(file.clone());
+    }
+    if let Some(recording) = &replay {
+        tui.start_replay(recording);
+    }
+    tui.run().await?;
+
+    // Written once the terminal is back to normal, so errors can be seen
+    let game = tui.game;
+    drop(guard);
+    if let Some(file) = &options.out {
+        if let Err(err) = write_live_cells(file, &game) {
+            eprintln!("error: can't write {}: {}", file, err);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+/// The TUI reads keys from stdin and draws on stdout, so it needs both to be
+/// a terminal. Scripts piping either get pointed at `--headless`.
+fn check_terminal(stdin: bool, stdout: bool) -> std::result::Result<(), String> {
+    match (stdin, stdout) {
+        (true, true) => Ok(()),
+        (_, false) => Err(
+            "the output isn't a terminal, use --headless to print the board instead".to_string(),
+        ),
+        (false, true) => Err(
+            "the input isn't a terminal, use --headless --format to pipe a pattern in".to_string(),
+        ),
+    }
+}
+
+/// Sets the rule the `--pattern` file is meant for, unless `--rule` asks for
+/// another one. Returns a warning saying so when they differ.
+fn apply_file_rule(
+    game: &mut Game,
+    options: &Options,
+    file: &str,
+    rule: Option<Rule>,
+) -> Option<String> {
+    match (rule, &options.rule) {
+        (Some(rule), Some(flag)) if rule != *flag => Some(format!(
+            "{} is for {}, running it with {} from --rule",
+            file, rule, flag
+        )),
+        (Some(rule), None) => {
+            game.set_rule(rule);
+            None
+        }
+        _ => None,
+    }
+}
+
+/// The live cells, cut to the smallest rectangle around them
+fn live_pattern(game: &Game) -> Pattern {
+    match game.live_bounds() {
+        Some(bounds) => game.copy_region(bounds),
+        None => Pattern::new(vec![]),
+    }
+}
+
+fn read_recording(file: &str) -> std::result::Result<Recording, String> {
+    std::fs::read_to_string(file)
+        .map_err(|error| error.to_string())
+        .and_then(|text| Recording::parse(&text).map_err(|error| error.to_string()))
+        .map_err(|error| format!("Can't replay {}: {}", file, error))
+}
+
+/// Writes the live cells to `file`, in the format going with its extension
+fn write_live_cells(file: &str, game: &Game) -> std::result::Result<(), String> {
+    let format = Format::from_path(Path::new(file)).ok_or("use .rle, .cells or .json")?;
+    std::fs::write(file, format.write(&live_pattern(game))).map_err(|error| error.to_string())
+}
+
+/// Reads the pattern in `file`, in the format its extension says, or
+/// whichever format it's in when the extension is unknown. RLE files can
+/// come with the rule they're meant for.
+fn read_pattern(file: &str) -> std::result::Result<(Pattern, Option<Rule>), String> {
+    std::fs::read_to_string(file)
+        .map_err(|error| error.to_string())
+        .and_then(|text| parse_pattern(&text, Format::from_path(Path::new(file))))
+        .map(|(pattern, rule)| {
+            let (width, height) = pattern.size();
+            log::info!("read {}, {}x{}", file, width, height);
+            (pattern, rule)
+        })
+        .map_err(|error| format!("Can't load {}: {}", file, error))
+}
+
+/// Reads the pattern piped in, for `--headless --format`
+fn read_piped_pattern(format: Format) -> std::result::Result<(Pattern, Option<Rule>), String> {
+    std::io::read_to_string(std::io::stdin())
+        .map_err(|error| error.to_string())
+        .and_then(|text| parse_pattern(&text, Some(format)))
+        .map_err(|error| format!("Can't read the pattern from stdin: {}", error))
+}
+
+/// Reads a pattern in `format`, or whichever format it's in, along with the
+/// rule in its RLE header if it has one
+fn parse_pattern(
+    text: &str,
+    format: Option<Format>,
+) -> std::result::Result<(Pattern, Option<Rule>), String> {
+    let mut pattern = match format {
+        Some(format) => format.parse(text),
+        None => format::parse(text),
+    }
+    .map_err(|error| error.to_string())?;
+    // Leading empty rows and columns would put it off centre
+    pattern.normalize();
+    let rule = format::rle_rule(text).map(str::parse).transpose()?;
+    Ok((pattern, rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output counting how often it's flushed
+    #[derive(Default)]
+    struct CountingWriter {
+        written: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_clamps_the_cursor_at_the_edges() {
+        assert_eq!(move_cursor((0, 0), (-1, 0), (5, 4), false), (0, 0));
+        assert_eq!(move_cursor((0, 0), (0, -1), (5, 4), false), (0, 0));
+        assert_eq!(move_cursor((4, 3), (1, 0), (5, 4), false), (4, 3));
+        assert_eq!(move_cursor((4, 3), (0, 1), (5, 4), false), (4, 3));
+        assert_eq!(move_cursor((2, 2), (1, -1), (5, 4), false), (3, 1));
+    }
+
+    #[test]
+    fn it_wraps_the_cursor_around_the_edges() {
+        assert_eq!(move_cursor((0, 0), (-1, 0), (5, 4), true), (4, 0));
+        assert_eq!(move_cursor((0, 0), (0, -1), (5, 4), true), (0, 3));
+        assert_eq!(move_cursor((4, 3), (1, 0), (5, 4), true), (0, 3));
+        assert_eq!(move_cursor((4, 3), (0, 1), (5, 4), true), (4, 0));
+        assert_eq!(move_cursor((2, 2), (1, -1), (5, 4), true), (3, 1));
+    }
+
+    #[test]
+    fn it_makes_disc_brushes() {
+        assert_eq!(brush_offsets(0), vec![(0, 0)]);
+        assert_eq!(brush_offsets(1).len(), 9);
+        let offsets = brush_offsets(2);
+        assert_eq!(offsets.len(), 21);
+        assert!(offsets.contains(&(0, -2)));
+        assert!(offsets.contains(&(1, 2)));
+        assert!(!offsets.contains(&(2, 2)));
+    }
+
+    #[test]
+    fn it_draws_lines_without_gaps() {
+        assert_eq!(line((2, 2), (2, 2)), vec![(2, 2)]);
+        assert_eq!(line((0, 0), (3, 0)), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(line((3, 3), (0, 0)), vec![(3, 3), (2, 2), (1, 1), (0, 0)]);
+        assert_eq!(
+            line((0, 0), (4, 2)),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+        assert_eq!(line((1, 4), (1, 2)), vec![(1, 4), (1, 3), (1, 2)]);
+    }
+
+    #[test]
+    fn it_mirrors_positions() {
+        assert_eq!(Symmetry::None.positions(1, 2, 6, 5), vec![(1, 2)]);
+        assert_eq!(
+            Symmetry::Horizontal.positions(1, 2, 6, 5),
+            vec![(1, 2), (4, 2)]
+        );
+        assert_eq!(
+            Symmetry::Vertical.positions(1, 1, 6, 5),
+            vec![(1, 1), (1, 3)]
+        );
+        assert_eq!(
+            Symmetry::Both.positions(1, 1, 6, 5),
+            vec![(1, 1), (1, 3), (4, 1), (4, 3)]
+        );
+        // The centre of an odd board is its own image
+        assert_eq!(Symmetry::Both.positions(2, 2, 5, 5), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn it_rotates_positions_around_the_centre() {
+        assert_eq!(
+            Symmetry::FourFold.positions(1, 0, 5, 5),
+            vec![(0, 3), (1, 0), (3, 4), (4, 1)]
+        );
+        // On a non square board, images off the board are left out
+        assert_eq!(
+            Symmetry::FourFold.positions(0, 0, 6, 2),
+            vec![(0, 0), (5, 1)]
+        );
+    }
+
+    #[test]
+    fn it_orders_the_corners_of_a_selection() {
+        let mut selection = Selection {
+            start: (5, 1),
+            end: (2, 4),
+        };
+        assert_eq!(selection.rect(), (2, 1, 5, 4));
+
+        selection.translate(16, 0);
+        assert_eq!(selection.rect(), (18, 1, 21, 4));
+    }
+
+    #[test]
+    fn it_ticks_at_any_fps() {
+        let secs = |fps| tick_interval(fps).as_secs_f64();
+        assert!((secs(2.0) - 0.5).abs() < 1e-6);
+        assert!((secs(0.5) - 2.0).abs() < 1e-6);
+        assert!((secs(10.0) - 0.1).abs() < 1e-6);
+        assert!((secs(0.7) - 1.0 / 0.7).abs() < 1e-6);
+        assert_eq!(tick_interval(0.0), tick_interval(MIN_FPS));
+    }
+
+    #[test]
+    fn it_lowers_the_fps_for_slow_ticks() {
+        assert_eq!(adaptive_fps(6.0, Duration::from_millis(3)), 6.0);
+        assert_eq!(adaptive_fps(6.0, Duration::from_millis(400)), 2.0);
+        assert_eq!(adaptive_fps(6.0, Duration::ZERO), 6.0);
+    }
+
+    #[test]
+    fn it_measures_rates_over_the_last_second() {
+        let start = std::time::Instant::now();
+        let mut meter = RateMeter::default();
+        assert_eq!(meter.rate(), None);
+
+        meter.record(start);
+        assert_eq!(meter.rate(), None);
+        for i in 1..=20 {
+            meter.record(start + Duration::from_millis(100) * i);
+        }
+        assert_eq!(meter.rate(), Some(10.0));
+
+        // Slower than once per window still has a rate
+        meter.record(start + Duration::from_secs(6));
+        assert_eq!(meter.rate(), Some(0.25));
+    }
+
+    #[test]
+    fn it_dims_older_cells() {
+        assert_eq!(
+            age_color(0),
+            style::Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }
+        );
+        assert_eq!(age_color(AGE_RAMP), age_color(AGE_RAMP * 10));
+        assert_eq!(
+            age_color(AGE_RAMP),
+            style::Color::Rgb {
+                r: 110,
+                g: 110,
+                b: 110
+            }
+        );
+    }
+
+    #[test]
+    fn it_renders_plain_text() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(5, 4);
+        tui.screen = (5, 4);
+        tui.show_status_bar = false;
+        tui.game.stamp(&Pattern::preset("blinker").unwrap(), 1, 1);
+
+        tui.render_plain().unwrap();
+        tui.game.tick();
+        tui.render_plain().unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ".....\n.OOO.\n.....\n.....\n..O..\n..O..\n..O..\n.....\n"
+        );
+    }
+
+    #[test]
+    fn it_repaints_when_the_terminal_is_resized() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(5, 3);
+        tui.screen = (5, 3);
+        tui.show_status_bar = false;
+        tui.game.stamp(&Pattern::preset("blinker").unwrap(), 1, 1);
+
+        tui.handle_event(Event::Resize(5, 3)).unwrap();
+        assert!(tui.writer.is_empty(), "the size didn't change");
+
+        tui.handle_event(Event::Resize(6, 4)).unwrap();
+        assert!(!tui.writer.is_empty());
+        assert_eq!(tui.game.size(), (6, 4));
+        tui.writer.clear();
+        tui.render_plain().unwrap();
+        assert_eq!(
+            String::from_utf8(tui.writer.clone()).unwrap(),
+            "......\n.OOO..\n......\n......\n"
+        );
+    }
+
+    #[test]
+    fn it_keeps_a_fixed_world_size_in_a_small_terminal() {
+        let mut output = vec![];
+        let options = Options {
+            width: Some(200),
+            height: Some(200),
+            ..Options::default()
+        };
+        let mut tui = TuiGame::new(&mut output, &options, Keymap::default());
+        assert_eq!(tui.game.size(), (200, 200));
+
+        tui.game
+            .stamp(&Pattern::preset("blinker").unwrap(), 150, 150);
+        tui.resize(20, 8).unwrap();
+        tui.toggle_status_bar().unwrap();
+        assert_eq!(tui.game.size(), (200, 200));
+
+        // The view is a window into the board
+        tui.viewport = (149, 149);
+        tui.show_status_bar = false;
+        tui.screen = (5, 3);
+        tui.writer.clear();
+        tui.render_plain().unwrap();
+        assert_eq!(
+            String::from_utf8(tui.writer.clone()).unwrap(),
+            ".....\n.OOO.\n.....\n"
+        );
+    }
+
+    #[test]
+    fn it_picks_a_pattern_to_place() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.playing = true;
+
+        tui.handle_key(KeyCode::Char('o'), KeyModifiers::NONE)
+            .unwrap();
+        assert!(!tui.playing);
+        for code in [KeyCode::Down, KeyCode::Down, KeyCode::Enter] {
+            tui.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+                .unwrap();
+        }
+
+        assert!(tui.picker.is_none());
+        let placing = tui.placing.as_ref().unwrap();
+        assert_eq!(placing.name, "toad");
+        assert_eq!(placing.pattern, Pattern::preset("toad").unwrap());
+    }
+
+    #[test]
+    fn it_starts_playing_with_play() {
+        let mut output = vec![];
+        let options = Options::parse(["--play"]).unwrap().unwrap();
+        let mut tui = TuiGame::new(&mut output, &options, Keymap::default());
+        tui.game = Game::new(10, 10);
+        assert_eq!(tui.play_indicator(), PLAYING_INDICATOR);
+
+        tui.tick().unwrap();
+        assert!(tui.playing, "an empty board keeps ticking");
+        for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+            tui.game.revive_cell_at_pos(x, y).unwrap();
+        }
+        tui.tick().unwrap();
+        assert!(!tui.playing, "a still life pauses");
+    }
+
+    #[test]
+    fn it_pauses_at_the_max_generations() {
+        let mut output = vec![];
+        let options = Options {
+            max_generations: Some(2),
+            ..Options::default()
+        };
+        let mut tui = TuiGame::new(&mut output, &options, Keymap::default());
+        tui.game = Game::new(10, 10);
+        tui.game.stamp(&Pattern::preset("blinker").unwrap(), 3, 3);
+        tui.playing = true;
+
+        tui.tick().unwrap();
+        assert!(tui.playing);
+        tui.tick().unwrap();
+        assert!(!tui.playing);
+        assert_eq!(tui.game.generation(), 2);
+        assert_eq!(tui.toasts.front().unwrap(), "Stopped at generation 2");
+
+        // Stepping past it by hand
+        tui.handle_key(KeyCode::Right, KeyModifiers::NONE).unwrap();
+        assert_eq!(tui.game.generation(), 3);
+    }
+
+    #[test]
+    fn it_draws_the_grid_on_dead_cells_only() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(12, 12);
+        tui.game.revive_cell_at_pos(5, 5).unwrap();
+        let hash = KeyCode::Char('#');
+
+        tui.handle_key(hash, KeyModifiers::NONE).unwrap();
+        assert_eq!(tui.grid_every, Some(5));
+        let on_grid =
+            |tui: &TuiGame<_>, x, y| tui.on_grid(tui.game.find_cell_at_pos(x, y).unwrap(), x, y);
+        assert!(on_grid(&tui, 5, 3));
+        assert!(on_grid(&tui, 3, 10));
+        assert!(!on_grid(&tui, 3, 3));
+        assert!(!on_grid(&tui, 5, 5), "live cells aren't covered");
+
+        tui.handle_key(hash, KeyModifiers::NONE).unwrap();
+        assert_eq!(tui.grid_every, Some(10));
+        tui.handle_key(hash, KeyModifiers::NONE).unwrap();
+        assert_eq!(tui.grid_every, None);
+        assert!(!on_grid(&tui, 0, 0));
+    }
+
+    #[test]
+    fn it_draws_plain_characters_without_colour() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(6, 6);
+        tui.game.revive_cell_at_pos(2, 2).unwrap();
+        tui.color = false;
+        let cell_at = |tui: &TuiGame<_>, x, y| {
+            let content = tui.styled_cell(tui.game.find_cell_at_pos(x, y).unwrap(), x, y);
+            (*content.content(), *content.style())
+        };
+
+        let (alive, style) = cell_at(&tui, 2, 2);
+        assert_eq!(alive, "O");
+        assert_eq!(style.foreground_color, None);
+        assert_eq!(style.background_color, None);
+        assert_eq!(cell_at(&tui, 1, 1).0, " ");
+
+        tui.cursor = Some((2, 2));
+        let (_, style) = cell_at(&tui, 2, 2);
+        assert_eq!(style.background_color, None);
+        assert!(style.attributes.has(style::Attribute::Reverse));
+
+        let bar = styled("bar".black().on_grey(), false);
+        assert_eq!(bar.style().background_color, None);
+        assert!(bar.style().attributes.has(style::Attribute::Reverse));
+        assert_eq!(
+            styled("bar".black().on_grey(), true),
+            "bar".black().on_grey()
+        );
+    }
+
+    #[test]
+    fn it_records_the_run_and_replays_it() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(12, 12);
+        tui.game.stamp(&Pattern::preset("glider").unwrap(), 1, 1);
+        let file = std::env::temp_dir().join(format!("gameoflife-{}.replay", std::process::id()));
+        let file = file.to_str().unwrap();
+
+        tui.tick().unwrap();
+        // Editing starts a new run
+        tui.set_board_cell(10, 10, true).unwrap();
+        tui.set_board_cell(10, 10, false).unwrap();
+        for _ in 0..5 {
+            tui.tick().unwrap();
+        }
+        assert_eq!(
+            tui.run_command(&format!("record {}", file)),
+            Ok(format!("Recorded 5 generations in {}", file))
+        );
+        let recording = read_recording(file).unwrap();
+        std::fs::remove_file(file).unwrap();
+
+        tui.start_replay(&recording);
+        assert_eq!(tui.game.generation(), 0);
+        assert!(tui.playing);
+        for _ in 0..5 {
+            tui.tick().unwrap();
+        }
+        assert!(!tui.playing);
+        let mut expected = Game::new(12, 12);
+        expected.stamp(&Pattern::preset("glider").unwrap(), 1, 1);
+        expected.tick_to(6, |_| {});
+        assert_eq!(
+            tui.game.live_cells().collect::<Vec<_>>(),
+            expected.live_cells().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_runs_commands_from_the_palette() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(10, 10);
+
+        tui.handle_key(KeyCode::Char(':'), KeyModifiers::NONE)
+            .unwrap();
+        for c in "rule B36/S23".chars() {
+            tui.handle_prompt_key(KeyCode::Char(c)).unwrap();
+        }
+        tui.handle_prompt_key(KeyCode::Enter).unwrap();
+        assert!(tui.prompt.is_none());
+        assert_eq!(tui.game.rule(), &Rule::new(&[3, 6], &[2, 3]));
+        assert_eq!(tui.toasts.front().unwrap(), "Rule B36/S23");
+
+        assert!(tui.run_command("random 1").is_ok());
+        assert_eq!(tui.game.population(), 100);
+        assert!(tui.run_command("clear").is_ok());
+        assert_eq!(tui.game.population(), 0);
+        assert!(tui.run_command("fps 12").is_ok());
+        assert_eq!(tui.fps, 12.0);
+        assert!(tui.run_command("fps fast").is_err());
+    }
+
+    #[test]
+    fn it_draws_in_the_paint_colour() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(10, 10);
+        tui.set_board_cell(1, 1, true).unwrap();
+
+        tui.handle_key(KeyCode::Char('C'), KeyModifiers::SHIFT)
+            .unwrap();
+        tui.set_board_cell(2, 1, true).unwrap();
+
+        let color_at = |tui: &TuiGame<_>, x, y| tui.game.find_cell_at_pos(x, y).unwrap().color();
+        assert_eq!(color_at(&tui, 1, 1), None);
+        assert_eq!(color_at(&tui, 2, 1), Some(PAINT_COLORS[0].1));
+        assert_eq!(tui.extra_status(), vec!["drawing red"]);
+
+        tui.paint_color = None;
+        tui.set_board_cell(2, 1, true).unwrap();
+        assert_eq!(color_at(&tui, 2, 1), Some(PAINT_COLORS[0].1));
+    }
+
+    #[test]
+    fn it_previews_the_pattern_until_placed() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(10, 10);
+        tui.start_placing("blinker", Pattern::preset("blinker").unwrap())
+            .unwrap();
+        tui.move_placing(2, 3);
+
+        assert!(tui.is_placed(3, 3));
+        assert_eq!(tui.game.population(), 0);
+        tui.handle_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+        assert!(tui.placing.is_none());
+        assert_eq!(tui.game.population(), 0);
+
+        tui.start_placing("blinker", Pattern::preset("blinker").unwrap())
+            .unwrap();
+        tui.move_placing(2, 3);
+        tui.handle_key(KeyCode::Enter, KeyModifiers::NONE).unwrap();
+        let live: Vec<_> = tui.game.live_cells().collect();
+        assert_eq!(live, vec![(2, 3), (3, 3), (4, 3)]);
+    }
+
+    #[test]
+    fn it_scrolls_the_picker_to_the_selection() {
+        let mut picker = Picker {
+            selected: 7,
+            top: 0,
+        };
+        picker.scroll(5);
+        assert_eq!(picker.top, 3);
+
+        picker.selected = 1;
+        picker.scroll(5);
+        assert_eq!(picker.top, 1);
+    }
+
+    #[test]
+    fn it_lists_loaded_patterns_in_the_picker() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        let tall = Pattern::from_rows(&["OO", "OO", "OO"]);
+        tui.loaded_patterns.push(("tall.cells".to_string(), tall));
+
+        let lines = tui.picker_lines();
+
+        assert_eq!(lines.len(), Pattern::preset_names().count() + 1);
+        let last = lines.last().unwrap();
+        assert!(
+            last.starts_with("tall.cells") && last.ends_with("2x3"),
+            "{}",
+            last
+        );
+    }
+
+    #[test]
+    fn it_maps_mouse_positions_to_cells_two_characters_wide() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(40, 20);
+        tui.screen = (40, 21);
+        tui.aspect_correct = true;
+        tui.viewport = (3, 1);
+        let mouse = |kind, column| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row: 2,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        assert_eq!(tui.board_view_size(), (20, 20));
+        assert_eq!(tui.to_board_pos(4, 2), (5, 3));
+        assert_eq!(tui.to_board_pos(5, 2), (5, 3));
+        assert_eq!(tui.to_screen_pos(5, 3), Some((4, 2)));
+        assert_eq!(tui.to_screen_pos(23, 3), None);
+
+        tui.handle_event(mouse(MouseEventKind::Down(MouseButton::Middle), 5))
+            .unwrap();
+        assert!(tui.game.find_cell_at_pos(5, 3).unwrap().is_alive());
+        // Each cell crossed is toggled once, not once per column
+        tui.handle_event(mouse(MouseEventKind::Drag(MouseButton::Middle), 9))
+            .unwrap();
+        let alive: Vec<bool> = (4..9)
+            .map(|x| tui.game.find_cell_at_pos(x, 3).unwrap().is_alive())
+            .collect();
+        assert_eq!(alive, [false, true, true, true, false]);
+    }
+
+    #[test]
+    fn it_leaves_flushing_mouse_edits_to_the_run_loop() {
+        let mut output = CountingWriter::default();
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(40, 20);
+        tui.screen = (40, 21);
+        let mouse = |kind, column| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row: 3,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+
+        tui.handle_event(mouse(MouseEventKind::Down(MouseButton::Left), 2))
+            .unwrap();
+        tui.handle_event(mouse(MouseEventKind::Drag(MouseButton::Left), 6))
+            .unwrap();
+
+        assert_eq!(tui.game.population(), 5);
+        assert!(!tui.writer.written.is_empty());
+        assert_eq!(tui.writer.flushes, 0);
+    }
+
+    #[test]
+    fn it_needs_a_terminal_for_the_tui() {
+        assert_eq!(check_terminal(true, true), Ok(()));
+        assert!(check_terminal(true, false)
+            .unwrap_err()
+            .contains("--headless"));
+        assert!(check_terminal(false, false).unwrap_err().contains("output"));
+        assert!(check_terminal(false, true).unwrap_err().contains("input"));
+    }
+
+    #[test]
+    fn it_shows_board_errors_and_stops_on_terminal_ones() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        let out_of_bounds = tui.game.kill_cell_at_pos(u16::MAX, 0).unwrap_err();
+        let message = out_of_bounds.to_string();
+
+        tui.report(out_of_bounds).unwrap();
+        assert_eq!(tui.toasts.front(), Some(&message));
+        let broken = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "gone");
+        assert!(tui.report(GameError::Io(broken)).is_err());
+    }
+
+    #[test]
+    fn it_asks_before_quitting_with_unsaved_edits() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        let q = KeyCode::Char('q');
+        assert_eq!(tui.handle_key(q, KeyModifiers::NONE).unwrap(), Flow::Quit);
+
+        tui.set_board_cell(3, 3, true).unwrap();
+        assert!(tui.dirty);
+        assert_eq!(
+            tui.handle_key(q, KeyModifiers::NONE).unwrap(),
+            Flow::Continue
+        );
+        let esc = tui.handle_key(KeyCode::Esc, KeyModifiers::NONE).unwrap();
+        assert_eq!(esc, Flow::Continue);
+        assert!(!tui.pending_quit);
+
+        // Twice in a row quits, and so does Ctrl+q straight away
+        assert_eq!(
+            tui.handle_key(q, KeyModifiers::NONE).unwrap(),
+            Flow::Continue
+        );
+        assert_eq!(tui.handle_key(q, KeyModifiers::NONE).unwrap(), Flow::Quit);
+        assert_eq!(
+            tui.handle_key(q, KeyModifiers::CONTROL).unwrap(),
+            Flow::Quit
+        );
+
+        // Ctrl+c, which doesn't interrupt in raw mode, asks like q
+        tui.pending_quit = false;
+        let c = KeyCode::Char('c');
+        assert_eq!(
+            tui.handle_key(c, KeyModifiers::CONTROL).unwrap(),
+            Flow::Continue
+        );
+        assert!(tui.pending_quit);
+        assert_eq!(
+            tui.handle_key(c, KeyModifiers::CONTROL).unwrap(),
+            Flow::Quit
+        );
+
+        // Clearing the board leaves nothing to ask about
+        tui.pending_quit = false;
+        tui.set_board_cell(3, 3, true).unwrap();
+        tui.run_command("clear").unwrap();
+        assert!(!tui.dirty);
+        assert_eq!(tui.handle_key(q, KeyModifiers::NONE).unwrap(), Flow::Quit);
+    }
+
+    #[test]
+    fn it_saves_and_loads_through_the_prompt() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(10, 10);
+        tui.game.stamp(&Pattern::preset("glider").unwrap(), 1, 1);
+        tui.dirty = true;
+        let file = std::env::temp_dir().join(format!("gameoflife-{}.cells", std::process::id()));
+        let file = file.to_str().unwrap();
+
+        tui.handle_key(KeyCode::Char('s'), KeyModifiers::CONTROL)
+            .unwrap();
+        for _ in DEFAULT_FILE_NAME.chars() {
+            tui.handle_prompt_key(KeyCode::Backspace).unwrap();
+        }
+        for c in file.chars() {
+            tui.handle_prompt_key(KeyCode::Char(c)).unwrap();
+        }
+        tui.handle_prompt_key(KeyCode::Enter).unwrap();
+        assert!(!tui.dirty);
+        assert_eq!(std::fs::read_to_string(file).unwrap(), ".O.\n..O\nOOO\n");
+
+        tui.game = Game::new(9, 5);
+        tui.handle_key(KeyCode::Char('o'), KeyModifiers::CONTROL)
+            .unwrap();
+        // The last file is suggested
+        tui.handle_prompt_key(KeyCode::Enter).unwrap();
+        std::fs::remove_file(file).unwrap();
+        let live: Vec<_> = tui.game.live_cells().collect();
+        assert_eq!(live, vec![(4, 1), (5, 2), (3, 3), (4, 3), (5, 3)]);
+        // And it can be picked from then on
+        let name = format!("gameoflife-{}.cells", std::process::id());
+        let lines = tui.picker_lines();
+        assert_eq!(lines.len(), Pattern::preset_names().count() + 1);
+        assert!(lines.last().unwrap().starts_with(&name));
+
+        assert_eq!(
+            tui.save("glider.txt"),
+            Err("Can't save glider.txt: use .rle, .cells or .json".to_string())
+        );
+        assert!(tui.load("/nonexistent/glider.rle").is_err());
+    }
+
+    #[test]
+    fn it_reads_patterns_whatever_the_extension() {
+        let file = std::env::temp_dir().join(format!("gameoflife-{}.txt", std::process::id()));
+        std::fs::write(&file, "#N Blinker\nx = 3, y = 1, rule = B36/S23\n3o!\n").unwrap();
+        let (pattern, rule) = read_pattern(file.to_str().unwrap()).unwrap();
+        std::fs::write(&file, "x = 3, y = 1\n3q!\n").unwrap();
+        let error = read_pattern(file.to_str().unwrap());
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(pattern.cells(), &[(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(rule, Some(Rule::new(&[3, 6], &[2, 3])));
+        assert!(error.unwrap_err().contains("line 2"));
+    }
+
+    #[test]
+    fn it_draws_progress_bars() {
+        assert_eq!(progress_bar(3, 10, 19), " 3/10 [###.......] ");
+        assert_eq!(progress_bar(10, 10, 19), " 10/10 [#########] ");
+        assert_eq!(progress_bar(1, 10, 4), " 1/1");
+    }
+
+    #[test]
+    fn it_updates_the_title_once_per_second() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(5, 5);
+        tui.game.stamp(&Pattern::preset("blinker").unwrap(), 1, 2);
+        tui.game.tick();
+        assert_eq!(tui.title(), "Game of Life — gen 1, pop 3 [paused]");
+
+        let start = std::time::Instant::now();
+        tui.update_title(start).unwrap();
+        tui.update_title(start + TITLE_INTERVAL / 2).unwrap();
+        tui.update_title(start + TITLE_INTERVAL).unwrap();
+        let output = String::from_utf8(tui.writer.clone()).unwrap();
+        assert_eq!(output.matches("Game of Life").count(), 2);
+    }
+
+    #[test]
+    fn it_shows_the_generation_in_the_corner() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(40, 20);
+        tui.screen = (40, 21);
+        assert_eq!(tui.generation_counter(), None);
+
+        tui.handle_key(KeyCode::Char('n'), KeyModifiers::NONE)
+            .unwrap();
+        tui.game.tick_to(999, |_| ());
+        assert_eq!(
+            tui.generation_counter(),
+            Some((" gen 999 ".to_string(), 31, 0))
+        );
+        tui.game.tick();
+        assert_eq!(
+            tui.generation_counter(),
+            Some((" gen 1000 ".to_string(), 30, 0))
+        );
+        assert!(tui.is_covered(30, 0));
+        assert!(!tui.is_covered(29, 0));
+
+        // Below the minimap
+        tui.show_minimap = true;
+        let (_, _, _, minimap_height) = tui.minimap_area().unwrap();
+        assert_eq!(tui.generation_counter().unwrap().2, minimap_height);
+    }
+
+    #[test]
+    fn it_queues_messages() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.screen = (40, 10);
+        tui.show_status_bar = false;
+        tui.toast("Saved glider.rle").unwrap();
+        tui.toast("Brush radius: 2").unwrap();
+        let start = tui.toast_since.unwrap();
+        assert!(tui.is_covered(0, 9));
+
+        tui.expire_toasts(start + TOAST_DURATION / 2).unwrap();
+        assert_eq!(tui.toasts.front().unwrap(), "Saved glider.rle");
+
+        tui.expire_toasts(start + TOAST_DURATION).unwrap();
+        assert_eq!(tui.toasts.front().unwrap(), "Brush radius: 2");
+        assert_eq!(tui.toast_since, Some(start + TOAST_DURATION));
+
+        tui.expire_toasts(start + TOAST_DURATION * 2).unwrap();
+        assert!(tui.toasts.is_empty());
+        assert_eq!(tui.toast_since, None);
+        assert!(!tui.is_covered(0, 9));
+    }
+
+    #[test]
+    fn it_pauses_once_the_board_settles() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(10, 10);
+        tui.screen = (10, 11);
+        tui.playing = true;
+        // Oscillators never settle
+        tui.game.stamp(&Pattern::preset("blinker").unwrap(), 3, 3);
+        tui.tick().unwrap();
+        assert!(tui.playing);
+
+        tui.game = Game::new(10, 10);
+        tui.game.stamp(&Pattern::from_rows(&["OO", "OO"]), 3, 3);
+        tui.tick().unwrap();
+        assert!(!tui.playing);
+        assert!(tui.is_covered(0, 0));
+        assert!(!tui.is_covered(PAUSED_INDICATOR.chars().count() as u16, 0));
+    }
+
+    #[test]
+    fn it_lists_the_bound_keys_in_the_help() {
+        let mut output = vec![];
+        let keymap = Keymap::from_config("quit = \"Ctrl+x\"").unwrap();
+        let tui = TuiGame::new(&mut output, &Options::default(), keymap);
+
+        let lines = tui.help_lines();
+
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("Ctrl+x") && line.ends_with("Quit")));
+        assert!(!lines
+            .iter()
+            .any(|line| line.contains("Esc") && line.ends_with("Quit")));
+    }
+
+    #[test]
+    fn it_quits_with_a_remapped_quit_key() {
+        let mut output = vec![];
+        let keymap = Keymap::from_config("quit = \"x\"").unwrap();
+        let mut tui = TuiGame::new(&mut output, &Options::default(), keymap);
+        tui.screen = (40, 21);
+
+        let q = tui.handle_key(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(q.unwrap(), Flow::Continue);
+        let x = tui.handle_key(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(x.unwrap(), Flow::Quit);
+    }
+
+    #[test]
+    fn it_switches_edges_and_shows_them_in_the_status_bar() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game.revive_cell_at_pos(0, 0).unwrap();
+        assert!(tui.status_bar().contains("| bounded edges"));
+
+        tui.handle_key(KeyCode::Char('t'), KeyModifiers::NONE)
+            .unwrap();
+
+        assert_eq!(tui.game.topology(), Topology::Toroidal);
+        assert!(tui.status_bar().contains("| wrapping edges"));
+        assert_eq!(tui.game.population(), 1);
+    }
+
+    #[test]
+    fn it_previews_the_next_generation() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        tui.game = Game::new(5, 5);
+        tui.screen = (40, 21);
+        tui.color = false;
+        tui.game.stamp(&Pattern::from_rows(&["OOO"]), 1, 2);
+        let content = |tui: &TuiGame<_>, x, y| {
+            let cell = tui.game.find_cell_at_pos(x, y).unwrap();
+            *tui.styled_cell(cell, x, y).content()
+        };
+
+        tui.handle_key(KeyCode::Char('v'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(content(&tui, 2, 1), "+");
+        assert_eq!(content(&tui, 1, 2), "x");
+        assert_eq!(content(&tui, 2, 2), "O");
+
+        // Drawing next to the blinker changes what happens around it
+        tui.handle_event(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 2,
+            row: 1,
+            modifiers: KeyModifiers::NONE,
+        }))
+        .unwrap();
+        assert_eq!(content(&tui, 2, 1), "O");
+        assert_eq!(content(&tui, 1, 1), "+");
+
+        tui.handle_key(KeyCode::Char('v'), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(content(&tui, 1, 1), " ");
+    }
+
+    #[test]
+    fn it_goes_on_without_the_mouse() {
+        let mut output = vec![];
+        let mut tui = TuiGame::new(&mut output, &Options::default(), Keymap::default());
+        assert!(tui
+            .help_lines()
+            .iter()
+            .any(|line| line.contains("Left click")));
+
+        tui.handle_key(KeyCode::Char('M'), KeyModifiers::SHIFT)
+            .unwrap();
+        assert!(!tui.mouse_capture);
+        assert!(tui.toasts.back().unwrap().contains("c edits cells"));
+        let lines = tui.help_lines();
+        assert!(!lines.iter().any(|line| line.contains("Left click")));
+        assert!(lines.iter().any(|line| line.contains("No mouse")));
+
+        tui.handle_key(KeyCode::Char('M'), KeyModifiers::SHIFT)
+            .unwrap();
+        assert!(tui.mouse_capture);
+        let written = String::from_utf8_lossy(&output);
+        assert!(written.contains("\x1b[?1000l"), "disabled mouse capture");
+        assert!(written.contains("\x1b[?1000h"), "enabled mouse capture");
+    }
 }
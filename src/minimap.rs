@@ -0,0 +1,166 @@
+use gameoflife::Game;
+
+/// Characters for increasing live cell densities
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Downsampled view of the whole board, where each character covers a block
+/// of cells and is shaded by how many of them are alive.
+pub struct Minimap {
+    /// Size of the block of cells covered by each character
+    block: (u16, u16),
+    width: u16,
+    height: u16,
+    /// Fraction of live cells in each block
+    density: Vec<f32>,
+    /// `Game::revision` the minimap was built from
+    revision: u64,
+}
+
+impl Minimap {
+    /// Builds a minimap of `game` no larger than `max_size` characters.
+    pub fn new(game: &Game, max_size: (u16, u16)) -> Self {
+        let (block, (width, height)) = layout(game.size(), max_size);
+
+        let len = width as usize * height as usize;
+        let mut alive = vec![0u32; len];
+        let mut total = vec![0u32; len];
+        for (cell, x, y) in game.iter_cells() {
+            let index = (y / block.1) as usize * width as usize + (x / block.0) as usize;
+            total[index] += 1;
+            if cell.is_alive() {
+                alive[index] += 1;
+            }
+        }
+        let density = alive
+            .iter()
+            .zip(total.iter())
+            .map(|(&alive, &total)| alive as f32 / total.max(1) as f32)
+            .collect();
+
+        Self {
+            block,
+            width,
+            height,
+            density,
+            revision: game.revision(),
+        }
+    }
+
+    /// Whether the minimap still matches `game` and would look the same at
+    /// `max_size`
+    pub fn is_current(&self, game: &Game, max_size: (u16, u16)) -> bool {
+        self.revision == game.revision()
+            && layout(game.size(), max_size) == (self.block, (self.width, self.height))
+    }
+
+    /// Size in characters of the minimap of a board, without building it
+    pub fn size_for(board_size: (u16, u16), max_size: (u16, u16)) -> (u16, u16) {
+        layout(board_size, max_size).1
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    pub fn block(&self) -> (u16, u16) {
+        self.block
+    }
+
+    pub fn density(&self, column: u16, row: u16) -> f32 {
+        self.density[row as usize * self.width as usize + column as usize]
+    }
+
+    /// Block character for the density at `(column, row)`. Any live cell
+    /// gives at least the lightest shade so lone cells don't disappear.
+    pub fn shade(&self, column: u16, row: u16) -> char {
+        let density = self.density(column, row);
+        if density <= 0.0 {
+            return SHADES[0];
+        }
+        let last = SHADES.len() - 1;
+        let index = (density * last as f32).ceil() as usize;
+        SHADES[index.clamp(1, last)]
+    }
+
+    /// Board position at the top-left of the block shown at `(column, row)`
+    pub fn to_board_pos(&self, column: u16, row: u16) -> (u16, u16) {
+        (column * self.block.0, row * self.block.1)
+    }
+
+    /// Minimap position of the block containing the board cell `(x, y)`
+    pub fn to_minimap_pos(&self, x: u16, y: u16) -> (u16, u16) {
+        (x / self.block.0, y / self.block.1)
+    }
+}
+
+/// Block size and minimap size for a board
+fn layout(board_size: (u16, u16), max_size: (u16, u16)) -> ((u16, u16), (u16, u16)) {
+    let (board_width, board_height) = board_size;
+    let block = (
+        board_width.div_ceil(max_size.0.max(1)).max(1),
+        board_height.div_ceil(max_size.1.max(1)).max(1),
+    );
+    let size = (
+        board_width.div_ceil(block.0),
+        board_height.div_ceil(block.1),
+    );
+    (block, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_shades_blocks_by_density() {
+        let mut game = Game::new(100, 40);
+        game.revive_cell_at_pos(0, 0).unwrap();
+        game.revive_cell_at_pos(99, 39).unwrap();
+        game.revive_cell_at_pos(50, 10).unwrap();
+
+        let minimap = Minimap::new(&game, (10, 4));
+
+        assert_eq!(minimap.block(), (10, 10));
+        assert_eq!(minimap.size(), (10, 4));
+        let lit: Vec<_> = (0..4)
+            .flat_map(|row| (0..10).map(move |column| (column, row)))
+            .filter(|&(column, row)| minimap.density(column, row) > 0.0)
+            .collect();
+        assert_eq!(lit, vec![(0, 0), (5, 1), (9, 3)]);
+        assert_eq!(minimap.density(0, 0), 0.01);
+        assert_eq!(minimap.shade(0, 0), '░');
+        assert_eq!(minimap.shade(1, 0), ' ');
+        assert_eq!(minimap.to_minimap_pos(57, 12), (5, 1));
+        assert_eq!(minimap.to_board_pos(5, 1), (50, 10));
+    }
+
+    #[test]
+    fn it_averages_density_over_partial_blocks() {
+        let mut game = Game::new(3, 3);
+        game.revive_cell_at_pos(0, 0).unwrap();
+        game.revive_cell_at_pos(0, 2).unwrap();
+        game.revive_cell_at_pos(2, 2).unwrap();
+
+        let minimap = Minimap::new(&game, (2, 2));
+
+        // The right and bottom blocks are cut short by the edge of the board
+        assert_eq!(minimap.block(), (2, 2));
+        assert_eq!(minimap.density(0, 0), 0.25);
+        assert_eq!(minimap.density(0, 1), 0.5);
+        assert_eq!(minimap.density(1, 0), 0.0);
+        assert_eq!(minimap.density(1, 1), 1.0);
+        assert_eq!(minimap.shade(1, 1), '█');
+    }
+
+    #[test]
+    fn it_goes_stale_when_the_board_changes() {
+        let mut game = Game::new(10, 10);
+        let minimap = Minimap::new(&game, (5, 5));
+        assert!(minimap.is_current(&game, (5, 5)));
+        assert!(!minimap.is_current(&game, (2, 2)));
+
+        game.revive_cell_at_pos(1, 1).unwrap();
+
+        assert!(!minimap.is_current(&game, (5, 5)));
+    }
+}
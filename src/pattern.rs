@@ -0,0 +1,208 @@
+/// Built-in patterns, drawn with `O` for live cells and `.` for dead ones
+const PRESETS: &[(&str, &[&str])] = &[
+    ("glider", &[".O.", "..O", "OOO"]),
+    ("blinker", &["OOO"]),
+    ("toad", &[".OOO", "OOO."]),
+    ("beacon", &["OO..", "OO..", "..OO", "..OO"]),
+    ("lwss", &[".O..O", "O....", "O...O", "OOOO."]),
+    ("r-pentomino", &[".OO", "OO.", ".O."]),
+    ("acorn", &[".O.....", "...O...", "OO..OOO"]),
+    ("diehard", &["......O.", "OO......", ".O...OOO"]),
+    (
+        "gosper-gun",
+        &[
+            "........................O...........",
+            "......................O.O...........",
+            "............OO......OO............OO",
+            "...........O...O....OO............OO",
+            "OO........O.....O...OO..............",
+            "OO........O...O.OO....O.O...........",
+            "..........O.....O.......O...........",
+            "...........O...O....................",
+            "............OO......................",
+        ],
+    ),
+];
+
+/// A group of live cells, stored as offsets from its top-left corner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    width: u16,
+    height: u16,
+    cells: Vec<(u16, u16)>,
+}
+
+impl Pattern {
+    /// Builds a pattern from the positions of its live cells. Its size is the
+    /// smallest one fitting all of them.
+    pub fn new(mut cells: Vec<(u16, u16)>) -> Self {
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells.dedup();
+        let width = cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let height = cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a pattern of the given size, which is grown if needed to fit
+    /// every cell. Useful to keep the dead margins of a copied region.
+    pub fn with_size(width: u16, height: u16, cells: Vec<(u16, u16)>) -> Self {
+        let pattern = Self::new(cells);
+        Self {
+            width: pattern.width.max(width),
+            height: pattern.height.max(height),
+            ..pattern
+        }
+    }
+
+    /// Builds a pattern from rows of text where `O` is a live cell and any
+    /// other character a dead one.
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let mut cells = vec![];
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == 'O' {
+                    cells.push((x as u16, y as u16));
+                }
+            }
+        }
+        Self::new(cells)
+    }
+
+    /// One of the built-in patterns, by name
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, rows)| Self::from_rows(rows))
+    }
+
+    /// Names of the built-in patterns, for `preset`
+    pub fn preset_names() -> impl Iterator<Item = &'static str> {
+        PRESETS.iter().map(|(name, _)| *name)
+    }
+
+    /// The pattern turned a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(x, y)| (self.height - 1 - y, x))
+            .collect();
+        Self::with_size(self.height, self.width, cells)
+    }
+
+    /// The pattern mirrored left to right
+    pub fn flip_h(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(x, y)| (self.width - 1 - x, y))
+            .collect();
+        Self::with_size(self.width, self.height, cells)
+    }
+
+    /// The pattern mirrored top to bottom
+    pub fn flip_v(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(x, y)| (x, self.height - 1 - y))
+            .collect();
+        Self::with_size(self.width, self.height, cells)
+    }
+
+    /// Moves the live cells up and left until they touch the top and left
+    /// edges, and shrinks the pattern to fit them, dropping every dead margin.
+    pub fn normalize(&mut self) {
+        let min_x = self.cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let min_y = self.cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        let cells = self
+            .cells
+            .iter()
+            .map(|&(x, y)| (x - min_x, y - min_y))
+            .collect();
+        *self = Self::new(cells);
+    }
+
+    /// Width and height of its bounding box
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Positions of the live cells, row by row
+    pub fn cells(&self) -> &[(u16, u16)] {
+        &self.cells
+    }
+
+    /// Whether `(x, y)` is one of its live cells
+    pub fn is_alive(&self, x: u16, y: u16) -> bool {
+        self.cells
+            .binary_search_by_key(&(y, x), |&(x, y)| (y, x))
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sizes_the_pattern_to_fit_its_cells() {
+        let glider = Pattern::new(vec![(2, 2), (1, 0), (2, 1), (0, 2), (1, 2), (2, 1)]);
+
+        assert_eq!(glider.size(), (3, 3));
+        assert_eq!(glider.cells(), &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+        assert!(glider.is_alive(2, 1));
+        assert!(!glider.is_alive(0, 0));
+    }
+
+    #[test]
+    fn it_builds_presets_from_rows() {
+        let glider = Pattern::preset("glider").unwrap();
+
+        assert_eq!(
+            glider,
+            Pattern::new(vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)])
+        );
+        assert_eq!(Pattern::preset("gosper-gun").unwrap().size(), (36, 9));
+        assert_eq!(Pattern::preset("nope"), None);
+        assert!(Pattern::preset_names().all(|name| Pattern::preset(name).is_some()));
+    }
+
+    #[test]
+    fn it_normalizes_to_the_top_left_corner() {
+        let mut pattern = Pattern::with_size(12, 12, vec![(5, 5), (6, 5), (7, 7)]);
+
+        pattern.normalize();
+
+        assert_eq!(pattern.cells(), &[(0, 0), (1, 0), (2, 2)]);
+        assert_eq!(pattern.size(), (3, 3));
+
+        let mut empty = Pattern::with_size(4, 4, vec![]);
+        empty.normalize();
+        assert_eq!(empty.size(), (0, 0));
+    }
+
+    #[test]
+    fn it_rotates_and_flips() {
+        let glider = Pattern::preset("glider").unwrap();
+
+        let rotated = glider.rotate_cw();
+        assert_eq!(rotated, Pattern::from_rows(&["O..", "O.O", "OO."]));
+        let rotated_back = rotated.rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(rotated_back, glider);
+
+        assert_eq!(glider.flip_h(), Pattern::from_rows(&[".O.", "O..", "OOO"]));
+        assert_eq!(glider.flip_v(), Pattern::from_rows(&["OOO", "..O", ".O."]));
+        assert_eq!(glider.flip_h().flip_h(), glider);
+
+        // Dead margins turn along with the cells
+        let bar = Pattern::with_size(3, 2, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(bar.rotate_cw().size(), (2, 3));
+        assert_eq!(bar.rotate_cw().cells(), &[(1, 0), (1, 1), (1, 2)]);
+    }
+}
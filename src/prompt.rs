@@ -0,0 +1,109 @@
+use crossterm::event::KeyCode;
+
+/// What a key did to the line being edited
+#[derive(Debug, PartialEq)]
+pub enum Edit {
+    Continue,
+    Cancel,
+    Submit(String),
+}
+
+/// A single line of text input, edited with the usual keys
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineEditor {
+    input: Vec<char>,
+    /// Index in `input` of the character the cursor is on, `input.len()`
+    /// at the end
+    cursor: usize,
+}
+
+impl LineEditor {
+    /// Starts with `input` filled in and the cursor at its end
+    pub fn new(input: &str) -> Self {
+        let input: Vec<char> = input.chars().collect();
+        Self {
+            cursor: input.len(),
+            input,
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Edit {
+        match code {
+            KeyCode::Esc => return Edit::Cancel,
+            KeyCode::Enter => return Edit::Submit(self.input.iter().collect()),
+            KeyCode::Char(c) => {
+                self.input.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.len(),
+            _ => (),
+        }
+        Edit::Continue
+    }
+
+    /// The part of the input that fits in `width` columns, scrolled to keep
+    /// the cursor in view, and the column the cursor is at in it
+    pub fn visible(&self, width: usize) -> (String, usize) {
+        // The cursor needs a column too when it's past the end
+        let width = width.max(1);
+        let start = (self.cursor + 1).saturating_sub(width);
+        let visible = self.input.iter().skip(start).take(width).collect();
+        (visible, self.cursor - start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_keys(editor: &mut LineEditor, codes: &[KeyCode]) {
+        for &code in codes {
+            assert_eq!(editor.handle_key(code), Edit::Continue);
+        }
+    }
+
+    #[test]
+    fn it_edits_the_line() {
+        let mut editor = LineEditor::new("glider.rle");
+        type_keys(
+            &mut editor,
+            &[
+                KeyCode::Left,
+                KeyCode::Left,
+                KeyCode::Left,
+                KeyCode::Backspace,
+                KeyCode::Char('_'),
+                KeyCode::Home,
+                KeyCode::Delete,
+                KeyCode::Char('G'),
+            ],
+        );
+
+        assert_eq!(
+            editor.handle_key(KeyCode::Enter),
+            Edit::Submit("Glider_rle".to_string())
+        );
+        assert_eq!(editor.handle_key(KeyCode::Esc), Edit::Cancel);
+    }
+
+    #[test]
+    fn it_scrolls_long_input() {
+        let mut editor = LineEditor::new("patterns/glider.rle");
+
+        assert_eq!(editor.visible(8), ("der.rle".to_string(), 7));
+        assert_eq!(editor.visible(40), ("patterns/glider.rle".to_string(), 19));
+
+        type_keys(&mut editor, &[KeyCode::Home, KeyCode::Right]);
+        assert_eq!(editor.visible(8), ("patterns".to_string(), 1));
+    }
+}
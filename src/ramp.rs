@@ -0,0 +1,87 @@
+use crossterm::style::Color;
+
+/// Maps values from 0.0 to 1.0 to colours, blending between evenly spaced
+/// stops. Used for the states of decaying cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRamp {
+    name: &'static str,
+    stops: &'static [(u8, u8, u8)],
+}
+
+/// Every preset, by name
+pub const PRESETS: [ColorRamp; 2] = [ColorRamp::HEAT, ColorRamp::GRAYSCALE];
+
+impl ColorRamp {
+    /// Dark red through red to orange
+    pub const HEAT: Self = Self {
+        name: "heat",
+        stops: &[(80, 0, 0), (220, 40, 0), (255, 160, 0)],
+    };
+
+    /// Dark to light grey
+    pub const GRAYSCALE: Self = Self {
+        name: "grayscale",
+        stops: &[(40, 40, 40), (200, 200, 200)],
+    };
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        PRESETS.iter().find(|ramp| ramp.name == name).cloned()
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Colour for `value`, which is clamped to 0.0..=1.0
+    pub fn at(&self, value: f32) -> Color {
+        let last = self.stops.len() - 1;
+        let position = value.clamp(0.0, 1.0) * last as f32;
+        let index = (position as usize).min(last.saturating_sub(1));
+        let (from, to) = (self.stops[index], self.stops[(index + 1).min(last)]);
+        let fraction = position - index as f32;
+        let blend = |from: u8, to: u8| {
+            (f32::from(from) + (f32::from(to) - f32::from(from)) * fraction).round() as u8
+        };
+        Color::Rgb {
+            r: blend(from.0, to.0),
+            g: blend(from.1, to.1),
+            b: blend(from.2, to.2),
+        }
+    }
+}
+
+impl Default for ColorRamp {
+    fn default() -> Self {
+        Self::HEAT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_gives_distinct_colours_along_the_ramp() {
+        for ramp in PRESETS {
+            let (start, middle, end) = (ramp.at(0.0), ramp.at(0.5), ramp.at(1.0));
+            assert_ne!(start, middle, "{}", ramp.name());
+            assert_ne!(middle, end, "{}", ramp.name());
+            assert_ne!(start, end, "{}", ramp.name());
+        }
+
+        assert_eq!(
+            ColorRamp::HEAT.at(0.5),
+            Color::Rgb {
+                r: 220,
+                g: 40,
+                b: 0
+            }
+        );
+        assert_eq!(ColorRamp::HEAT.at(7.0), ColorRamp::HEAT.at(1.0));
+        assert_eq!(
+            ColorRamp::from_name("grayscale"),
+            Some(ColorRamp::GRAYSCALE)
+        );
+        assert_eq!(ColorRamp::from_name("rainbow"), None);
+    }
+}
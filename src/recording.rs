@@ -0,0 +1,162 @@
+use crate::format::{self, ParseError};
+use crate::game::{Game, Topology};
+use crate::pattern::Pattern;
+use crate::rule::Rule;
+
+/// A run of the game, kept as the board it started from and how many
+/// generations it went on for. Every generation follows from the one before,
+/// so that's enough to play it again exactly.
+///
+/// Saved as RLE with a `#Replay` comment line first, which other programs
+/// skip, so the starting board can be opened anywhere:
+///
+/// ```text
+/// #Replay size = 40x20, generations = 120, rule = B3/S23, topology = bounded
+/// x = 40, y = 20
+/// 17b3o!
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// The whole starting board, dead margins included
+    pub start: Pattern,
+    /// Rule it was played by
+    pub rule: Rule,
+    /// Edges it was played with
+    pub topology: Topology,
+    /// How many generations it went on for
+    pub generations: u64,
+}
+
+impl Recording {
+    /// Starts recording from the board as it is now, with no generations yet.
+    /// Custom rules can't be saved, the B/S rule is kept instead.
+    pub fn new(game: &Game) -> Self {
+        let (width, height) = game.size();
+        Self {
+            start: Pattern::with_size(width, height, game.live_cells().collect()),
+            rule: game.rule().clone(),
+            topology: game.topology(),
+            generations: 0,
+        }
+    }
+
+    /// The board the run started from, to tick `generations` times
+    pub fn game(&self) -> Game {
+        let (width, height) = self.start.size();
+        let mut game = Game::new(width, height);
+        game.set_rule(self.rule.clone());
+        game.set_topology(self.topology);
+        game.stamp(&self.start, 0, 0);
+        game
+    }
+
+    /// Writes it as it's saved
+    pub fn to_text(&self) -> String {
+        let (width, height) = self.start.size();
+        format!(
+            "#Replay size = {}x{}, generations = {}, rule = {}, topology = {}\n{}",
+            width,
+            height,
+            self.generations,
+            self.rule,
+            topology_name(self.topology),
+            format::to_rle(&self.start)
+        )
+    }
+
+    /// Reads it back from what `to_text` wrote
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let error = |line, message: &str| ParseError {
+            line,
+            message: message.to_string(),
+        };
+        let header = text
+            .lines()
+            .next()
+            .and_then(|line| line.trim().strip_prefix("#Replay"))
+            .ok_or_else(|| error(1, "expected a `#Replay ...` line first"))?;
+        let field = |name: &str| {
+            header
+                .split(',')
+                .filter_map(|part| part.split_once('='))
+                .find(|(key, _)| key.trim() == name)
+                .map(|(_, value)| value.trim())
+                .ok_or_else(|| error(1, &format!("missing `{}` in the #Replay line", name)))
+        };
+
+        let (width, height) = field("size")?
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(|| error(1, "invalid size, expected WIDTHxHEIGHT"))?;
+        let generations = field("generations")?
+            .parse()
+            .map_err(|_| error(1, "invalid generations, expected a number"))?;
+        let rule = field("rule")?
+            .parse()
+            .map_err(|message: String| error(1, &message))?;
+        let topology = topology_from_name(field("topology")?)
+            .ok_or_else(|| error(1, "invalid topology, expected bounded, toroidal or mirror"))?;
+        let cells = format::parse_rle(text)?;
+
+        Ok(Self {
+            start: Pattern::with_size(width, height, cells.cells().to_vec()),
+            rule,
+            topology,
+            generations,
+        })
+    }
+}
+
+fn topology_name(topology: Topology) -> &'static str {
+    match topology {
+        Topology::Bounded => "bounded",
+        Topology::Toroidal => "toroidal",
+        Topology::Mirror => "mirror",
+    }
+}
+
+fn topology_from_name(name: &str) -> Option<Topology> {
+    match name {
+        "bounded" => Some(Topology::Bounded),
+        "toroidal" => Some(Topology::Toroidal),
+        "mirror" => Some(Topology::Mirror),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_replays_to_the_same_state() {
+        let mut game = Game::new(30, 20);
+        game.set_topology(Topology::Toroidal);
+        game.set_rule(Rule::new(&[3, 6], &[2, 3]));
+        game.randomize_with_seed(7, 0.35);
+        let mut recording = Recording::new(&game);
+        game.tick_to(40, |_| {});
+        recording.generations = 40;
+
+        let replayed = Recording::parse(&recording.to_text()).unwrap();
+        assert_eq!(replayed, recording);
+        let mut replay = replayed.game();
+        replay.tick_to(replayed.generations, |_| {});
+
+        assert_eq!(replay.size(), game.size());
+        assert_eq!(
+            replay.live_cells().collect::<Vec<_>>(),
+            game.live_cells().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_reports_bad_headers() {
+        assert_eq!(Recording::parse("x = 1, y = 1\no!").unwrap_err().line, 1);
+        let missing = Recording::parse("#Replay size = 3x3, generations = 2\nx = 3, y = 3\no!");
+        assert_eq!(
+            missing.unwrap_err().message,
+            "missing `rule` in the #Replay line"
+        );
+    }
+}
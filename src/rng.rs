@@ -0,0 +1,50 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seed that's different on every run, from the clock
+pub(crate) fn seed_from_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+/// Mixed into seeds, so small ones don't start with mostly zero bits
+const SEED_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Small xorshift64* generator, good enough for filling boards
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // A zero state would only ever give zeros, and the one seed mixing
+        // into it gets another state instead
+        match seed ^ SEED_MIX {
+            0 => Self(SEED_MIX),
+            state => Self(state),
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform value in `0.0..1.0`
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_never_starts_from_a_zero_state() {
+        let mut rng = Rng::new(SEED_MIX);
+
+        assert!((0..10).any(|_| rng.next_u64() != 0));
+        assert!((0..10).any(|_| rng.next_f64() > 0.0));
+    }
+}
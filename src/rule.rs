@@ -0,0 +1,214 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Decides whether a cell is alive next tick, from whether it's alive now
+/// and how many live neighbours it has. Implement it for rules B/S notation
+/// can't express, and give it to `Game::set_custom_rule`.
+pub trait TransitionRule {
+    /// Whether the cell is alive next tick
+    fn next(&self, alive: bool, neighbours: u8) -> bool;
+}
+
+/// Birth/survival rule deciding the next state of each cell.
+///
+/// Rules with more than two `states` are "Generations" rules: instead of dying
+/// right away, cells that fail to survive go through `states - 2` decaying
+/// states before becoming dead. Decaying cells don't count as neighbours and
+/// can't be born again until they are fully dead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+    states: u8,
+}
+
+impl Rule {
+    /// A two state rule where dead cells with a neighbour count in `birth`
+    /// are born and live ones with a count in `survival` live on
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        Self::generations(birth, survival, 2)
+    }
+
+    /// Like `new`, with `states` states counting alive and dead, at least 2.
+    /// Live cells that don't survive decay through the states in between,
+    /// starting at `states - 1` and counting down, before they're dead.
+    pub fn generations(birth: &[u8], survival: &[u8], states: u8) -> Self {
+        Self {
+            birth: counts_to_table(birth),
+            survival: counts_to_table(survival),
+            states: states.max(2),
+        }
+    }
+
+    /// B3/S23
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    /// A few well-known rules to switch between at runtime
+    pub fn presets() -> Vec<Self> {
+        vec![
+            Self::conway(),
+            // HighLife
+            Self::new(&[3, 6], &[2, 3]),
+            // Star Wars
+            Self::generations(&[2], &[3, 4, 5], 4),
+            // Brian's Brain
+            Self::generations(&[2], &[], 3),
+        ]
+    }
+
+    /// Whether a dead cell with `neighbours` live neighbours is born
+    pub fn born(&self, neighbours: u8) -> bool {
+        self.birth.get(neighbours as usize) == Some(&true)
+    }
+
+    /// Whether a live cell with `neighbours` live neighbours stays alive
+    pub fn survives(&self, neighbours: u8) -> bool {
+        self.survival.get(neighbours as usize) == Some(&true)
+    }
+
+    /// Number of states, alive and dead included
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+}
+
+impl TransitionRule for Rule {
+    /// Whether the cell is born or survives. Generations rules decay cells
+    /// instead of killing them, which only `Game` keeps track of.
+    fn next(&self, alive: bool, neighbours: u8) -> bool {
+        if alive {
+            self.survives(neighbours)
+        } else {
+            self.born(neighbours)
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+/// B/S notation, like `B3/S23`, with the number of states for Generations
+/// rules, like `B2/S345/C4`
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |table: &[bool; 9]| -> String {
+            (0..=8)
+                .filter(|&n| table[n])
+                .map(|n| n.to_string())
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads B/S notation, like `B36/S23` or `B2/S345/C4`, in any case, as well
+/// as the older S/B notation, like `23/36`.
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rule {:?}, expected something like B3/S23", s);
+        let counts = |digits: &str| -> Result<Vec<u8>, String> {
+            digits
+                .chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Ok(n as u8),
+                    _ => Err(invalid()),
+                })
+                .collect()
+        };
+
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        if let [survival, birth] = parts[..] {
+            if survival
+                .chars()
+                .chain(birth.chars())
+                .all(|c| c.is_ascii_digit())
+            {
+                return Ok(Self::new(&counts(birth)?, &counts(survival)?));
+            }
+        }
+
+        let (mut birth, mut survival, mut states) = (None, None, None);
+        for part in parts {
+            let mut chars = part.chars();
+            let (slot, value) = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => (&mut birth, counts(chars.as_str())?),
+                Some('S') => (&mut survival, counts(chars.as_str())?),
+                Some('C') => {
+                    let count = chars.as_str().parse().ok().filter(|&n| n >= 2);
+                    if states.replace(count.ok_or_else(invalid)?).is_some() {
+                        return Err(invalid());
+                    }
+                    continue;
+                }
+                _ => return Err(invalid()),
+            };
+            if slot.replace(value).is_some() {
+                return Err(invalid());
+            }
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => {
+                Ok(Self::generations(&birth, &survival, states.unwrap_or(2)))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+fn counts_to_table(counts: &[u8]) -> [bool; 9] {
+    let mut table = [false; 9];
+    for &count in counts {
+        if let Some(entry) = table.get_mut(count as usize) {
+            *entry = true;
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_rulestrings() {
+        assert_eq!("B3/S23".parse(), Ok(Rule::conway()));
+        assert_eq!("b36/s23".parse(), Ok(Rule::new(&[3, 6], &[2, 3])));
+        assert_eq!("S23/B3".parse(), Ok(Rule::conway()));
+        assert_eq!("23/3".parse(), Ok(Rule::conway()));
+        assert_eq!("B2/S/C3".parse(), Ok(Rule::generations(&[2], &[], 3)));
+        for invalid in ["", "B3", "B9/S23", "B3/S23/C1", "B3/B4/S23", "life"] {
+            assert!(invalid.parse::<Rule>().is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn it_writes_rulestrings() {
+        let names: Vec<_> = Rule::presets().iter().map(Rule::to_string).collect();
+
+        assert_eq!(names, vec!["B3/S23", "B36/S23", "B2/S345/C4", "B2/S/C3"]);
+    }
+
+    #[test]
+    fn it_reads_back_what_it_writes() {
+        let conway = Rule::conway();
+        let highlife = Rule::new(&[6, 3], &[3, 2]);
+
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!(conway.to_string().parse(), Ok(conway));
+        assert_eq!(highlife.to_string().parse(), Ok(highlife));
+        for rule in Rule::presets() {
+            assert_eq!(rule.to_string().parse(), Ok(rule));
+        }
+    }
+}
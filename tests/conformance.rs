@@ -0,0 +1,98 @@
+//! Runs well-known patterns, read from RLE, for a few generations and checks
+//! the live cells against results worked out independently of this crate.
+
+use gameoflife::format::parse_rle;
+use gameoflife::game::Game;
+
+struct Case {
+    name: &'static str,
+    rle: &'static str,
+    /// Where the pattern's top-left corner is stamped, on a 48x48 board
+    at: (u16, u16),
+    generations: u64,
+    /// Live cells after `generations`, row by row
+    expected: &'static [(u16, u16)],
+}
+
+const CASES: &[Case] = &[
+    Case {
+        name: "blinker",
+        rle: "x = 3, y = 1\n3o!",
+        at: (8, 8),
+        generations: 1,
+        expected: &[(9, 7), (9, 8), (9, 9)],
+    },
+    Case {
+        name: "toad",
+        rle: "x = 4, y = 2\nb3o$3o!",
+        at: (8, 8),
+        generations: 1,
+        expected: &[(10, 7), (8, 8), (11, 8), (8, 9), (11, 9), (9, 10)],
+    },
+    Case {
+        name: "beacon",
+        rle: "x = 4, y = 4\n2o$2o$2b2o$2b2o!",
+        at: (8, 8),
+        generations: 1,
+        expected: &[(8, 8), (9, 8), (8, 9), (11, 10), (10, 11), (11, 11)],
+    },
+    Case {
+        name: "glider",
+        rle: "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!",
+        at: (8, 8),
+        generations: 4,
+        expected: &[(10, 9), (11, 10), (9, 11), (10, 11), (11, 11)],
+    },
+    Case {
+        name: "lwss",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+        at: (8, 8),
+        generations: 4,
+        expected: &[
+            (7, 8),
+            (10, 8),
+            (6, 9),
+            (6, 10),
+            (10, 10),
+            (6, 11),
+            (7, 11),
+            (8, 11),
+            (9, 11),
+        ],
+    },
+    Case {
+        name: "r-pentomino",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+        at: (20, 20),
+        generations: 10,
+        expected: &[
+            (18, 19),
+            (19, 19),
+            (17, 20),
+            (18, 20),
+            (18, 21),
+            (19, 21),
+            (19, 22),
+            (20, 22),
+            (21, 22),
+            (21, 23),
+            (21, 24),
+        ],
+    },
+];
+
+#[test]
+fn known_patterns_evolve_as_expected() {
+    for case in CASES {
+        let pattern = parse_rle(case.rle).unwrap();
+        let mut game = Game::new(48, 48);
+        assert!(game.stamp(&pattern, case.at.0, case.at.1), "{}", case.name);
+
+        for _ in 0..case.generations {
+            game.tick();
+        }
+
+        let live: Vec<_> = game.live_cells().collect();
+        assert_eq!(live, case.expected, "{}", case.name);
+    }
+}
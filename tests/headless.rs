@@ -0,0 +1,24 @@
+//! Runs the binary without the terminal UI, the way scripts use it.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn it_reads_a_piped_pattern() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gameoflife"))
+        .args(["--headless", "--format", "plaintext", "--generations", "1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"!Name: Blinker\nOOO\n")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "O\nO\nO\n");
+}